@@ -45,6 +45,14 @@ fn main() {
             )
         });
 
+    if std::env::var("CARGO_FEATURE_LIBSEAT").is_ok() {
+        pkg_config::Config::new()
+            .probe("libseat")
+            .unwrap_or_else(|_| {
+                panic!("Failed to link to libseat. Make sure it's installed on your system.")
+            });
+    }
+
     println!("cargo:rerun-if-changed=src/logger.c");
 
     cc::Build::new()
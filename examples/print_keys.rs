@@ -16,7 +16,7 @@ use tokio_stream::StreamExt;
 fn open_restricted(path: &CStr, flags: c_int) -> Result<RawFd, c_int> {
     open(path, OFlags::from_bits_retain(flags as u32), Mode::empty())
         .map(IntoRawFd::into_raw_fd)
-        .map_err(|err| err.raw_os_error().wrapping_neg())
+        .map_err(|err| err.raw_os_error())
 }
 
 fn close_restricted(fd: RawFd) {
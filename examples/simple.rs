@@ -1,28 +1,20 @@
-use std::{
-    ffi::{CStr, c_int},
-    os::fd::RawFd,
-};
-
-use colpetto::{Libinput, Result, event::AsRawEvent};
-use rustix::{
-    fd::{FromRawFd, IntoRawFd, OwnedFd},
-    fs::{Mode, OFlags, open},
-};
+use std::{io, os::fd::OwnedFd, path::Path};
+
+use colpetto::{Libinput, Logger, OFlags, OpenInterface, Result, event::AsRawEvent};
+use rustix::fs::{Mode, open};
 use tokio_stream::StreamExt;
 
-fn open_restricted(path: &CStr, flags: c_int) -> Result<RawFd, c_int> {
-    open(path, OFlags::from_bits_retain(flags as u32), Mode::empty())
-        .map(IntoRawFd::into_raw_fd)
-        .map_err(|err| err.raw_os_error().wrapping_neg())
-}
+struct Interface;
 
-fn close_restricted(fd: RawFd) {
-    drop(unsafe { OwnedFd::from_raw_fd(fd) });
+impl OpenInterface for Interface {
+    fn open(&mut self, path: &Path, flags: OFlags) -> io::Result<OwnedFd> {
+        Ok(open(path, flags, Mode::empty())?)
+    }
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let mut libinput = Libinput::with_tracing(open_restricted, close_restricted)?;
+    let mut libinput = Libinput::with_interface(Interface, Some(Logger::tracing()))?;
 
     libinput.udev_assign_seat(c"seat0")?;
 
@@ -0,0 +1,660 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    os::unix::ffi::OsStrExt,
+    path::{Path, PathBuf},
+};
+
+use devil::{Udev, sys};
+
+use crate::{Capabilities, DeviceCapability, DeviceProperties, InputId, udev::UdevDevice};
+
+/// Decides which devices colpetto passes to the open interface at all.
+///
+/// A policy is an ordered list of allow and deny rules, the first rule matching a device decides,
+/// devices matching no rule get the default access. Rules are built from [`DeviceMatch`]es, which
+/// look at what udev knows about a device before it is opened: its path, name, vendor and product ids,
+/// udev properties and the capabilities it is expected to have.
+///
+/// Devices rejected by the policy fail to open with `EACCES`, the rejection is logged with the rule
+/// that caused it and recorded in [`open_failures`](crate::Libinput::open_failures).
+/// Install a policy with [`Libinput::set_access_policy`](crate::Libinput::set_access_policy).
+///
+/// # Example usage
+///
+/// ```
+/// use colpetto::{AccessPolicy, DeviceCapability, DeviceMatch};
+///
+/// // Ignore usb keyboards plugged into the front panel
+/// let policy = AccessPolicy::allow_all().deny(
+///     DeviceMatch::property("ID_BUS", "usb")
+///         .and(DeviceMatch::capability(DeviceCapability::Keyboard))
+///         .and(DeviceMatch::property("ID_PATH", "pci-0000:00:14.0-usb-0:3*")),
+/// );
+///
+/// libinput.set_access_policy(Some(policy));
+/// libinput.udev_assign_seat(c"seat0")?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct AccessPolicy {
+    rules: Vec<(Access, DeviceMatch)>,
+    default: Access,
+}
+
+/// Whether a device may be opened
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Access {
+    /// The device is passed to the open interface
+    Allow,
+    /// The device is rejected without being opened
+    Deny,
+}
+
+impl fmt::Display for Access {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Allow => write!(f, "allow"),
+            Self::Deny => write!(f, "deny"),
+        }
+    }
+}
+
+/// The reason a device was rejected by an [`AccessPolicy`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{reason}")]
+pub struct AccessDenied {
+    reason: String,
+}
+
+impl AccessDenied {
+    /// A human readable description of the reason
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+impl AccessPolicy {
+    /// Creates a policy allowing every device not matched by a deny rule
+    pub fn allow_all() -> Self {
+        Self {
+            rules: Vec::new(),
+            default: Access::Allow,
+        }
+    }
+
+    /// Creates a policy denying every device not matched by an allow rule
+    pub fn deny_all() -> Self {
+        Self {
+            rules: Vec::new(),
+            default: Access::Deny,
+        }
+    }
+
+    /// Appends a rule allowing the devices matching `matcher`
+    pub fn allow(self, matcher: DeviceMatch) -> Self {
+        self.rule(Access::Allow, matcher)
+    }
+
+    /// Appends a rule denying the devices matching `matcher`
+    pub fn deny(self, matcher: DeviceMatch) -> Self {
+        self.rule(Access::Deny, matcher)
+    }
+
+    /// Appends a rule, rules are checked in the order they were added
+    pub fn rule(mut self, access: Access, matcher: DeviceMatch) -> Self {
+        self.rules.push((access, matcher));
+        self
+    }
+
+    /// Returns the access granted to `request`
+    pub fn decide(&self, request: &AccessRequest) -> Access {
+        self.check(request).map_or(Access::Deny, |()| Access::Allow)
+    }
+
+    /// Checks `request` against the rules, returning the reason if it is denied
+    pub fn check(&self, request: &AccessRequest) -> Result<(), AccessDenied> {
+        let rule = self
+            .rules
+            .iter()
+            .enumerate()
+            .find(|(_, (_, matcher))| matcher.matches(request));
+
+        match rule {
+            Some((_, (Access::Allow, _))) => Ok(()),
+            Some((index, (Access::Deny, matcher))) => Err(AccessDenied {
+                reason: format!("matches deny rule #{index}: {matcher}"),
+            }),
+            None => match self.default {
+                Access::Allow => Ok(()),
+                Access::Deny => Err(AccessDenied {
+                    reason: "matches no allow rule".to_owned(),
+                }),
+            },
+        }
+    }
+}
+
+/// A condition on the devices an [`AccessPolicy`] rule applies to.
+///
+/// Matchers can be combined with [`and`](Self::and), [`or`](Self::or) and `!`.
+/// Globs support `*`, matching any sequence of characters, and `?`, matching a single character.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DeviceMatch {
+    /// Matches every device
+    Any,
+    /// The device path matches the glob, e.g. `/dev/input/event*`
+    Path(String),
+    /// The device name matches the glob
+    Name(String),
+    /// The udev property is set to a value matching the glob
+    Property(String, String),
+    /// The device is expected to have the capability
+    Capability(DeviceCapability),
+    /// The device has the vendor id and, if set, the product id
+    VendorProduct(u32, Option<u32>),
+    /// Every matcher matches
+    All(Vec<DeviceMatch>),
+    /// At least one matcher matches
+    OneOf(Vec<DeviceMatch>),
+    /// The matcher does not match
+    Not(Box<DeviceMatch>),
+}
+
+impl DeviceMatch {
+    /// Matches devices whose path matches `glob`
+    pub fn path(glob: impl Into<String>) -> Self {
+        Self::Path(glob.into())
+    }
+
+    /// Matches devices whose name matches `glob`
+    pub fn name(glob: impl Into<String>) -> Self {
+        Self::Name(glob.into())
+    }
+
+    /// Matches devices with the udev property `key` set to a value matching `glob`
+    pub fn property(key: impl Into<String>, glob: impl Into<String>) -> Self {
+        Self::Property(key.into(), glob.into())
+    }
+
+    /// Matches devices expected to have `capability`, see [`AccessRequest::capabilities`]
+    pub fn capability(capability: DeviceCapability) -> Self {
+        Self::Capability(capability)
+    }
+
+    /// Matches devices from the given vendor
+    pub fn vendor(vendor: u32) -> Self {
+        Self::VendorProduct(vendor, None)
+    }
+
+    /// Matches devices with the given vendor and product ids
+    pub fn vendor_product(vendor: u32, product: u32) -> Self {
+        Self::VendorProduct(vendor, Some(product))
+    }
+
+    /// Matches devices matched by both `self` and `other`
+    pub fn and(self, other: Self) -> Self {
+        match self {
+            Self::All(mut matchers) => {
+                matchers.push(other);
+                Self::All(matchers)
+            }
+            matcher => Self::All(vec![matcher, other]),
+        }
+    }
+
+    /// Matches devices matched by either `self` or `other`
+    pub fn or(self, other: Self) -> Self {
+        match self {
+            Self::OneOf(mut matchers) => {
+                matchers.push(other);
+                Self::OneOf(matchers)
+            }
+            matcher => Self::OneOf(vec![matcher, other]),
+        }
+    }
+
+    /// Returns `true` if the device described by `request` is matched
+    pub fn matches(&self, request: &AccessRequest) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Path(glob) => glob_match(glob.as_bytes(), request.path.as_os_str().as_bytes()),
+            Self::Name(glob) => request
+                .name
+                .as_ref()
+                .is_some_and(|name| glob_match(glob.as_bytes(), name.as_bytes())),
+            Self::Property(key, glob) => request
+                .udev_properties
+                .get(key)
+                .is_some_and(|value| glob_match(glob.as_bytes(), value.as_bytes())),
+            Self::Capability(capability) => request.capabilities.contains(*capability),
+            Self::VendorProduct(vendor, product) => request.input_id.is_some_and(|id| {
+                id.vendor == *vendor && product.is_none_or(|product| id.product == product)
+            }),
+            Self::All(matchers) => matchers.iter().all(|matcher| matcher.matches(request)),
+            Self::OneOf(matchers) => matchers.iter().any(|matcher| matcher.matches(request)),
+            Self::Not(matcher) => !matcher.matches(request),
+        }
+    }
+}
+
+impl std::ops::Not for DeviceMatch {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        match self {
+            Self::Not(matcher) => *matcher,
+            matcher => Self::Not(Box::new(matcher)),
+        }
+    }
+}
+
+impl fmt::Display for DeviceMatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |f: &mut fmt::Formatter<'_>, matchers: &[Self], separator| {
+            write!(f, "(")?;
+
+            for (i, matcher) in matchers.iter().enumerate() {
+                if i > 0 {
+                    write!(f, " {separator} ")?;
+                }
+
+                write!(f, "{matcher}")?;
+            }
+
+            write!(f, ")")
+        };
+
+        match self {
+            Self::Any => write!(f, "any"),
+            Self::Path(glob) => write!(f, "path={glob}"),
+            Self::Name(glob) => write!(f, "name={glob:?}"),
+            Self::Property(key, glob) => write!(f, "{key}={glob}"),
+            Self::Capability(capability) => write!(f, "capability={capability}"),
+            Self::VendorProduct(vendor, None) => write!(f, "vendor={vendor:04x}"),
+            Self::VendorProduct(vendor, Some(product)) => {
+                write!(f, "vid:pid={vendor:04x}:{product:04x}")
+            }
+            Self::All(matchers) => join(f, matchers, "and"),
+            Self::OneOf(matchers) => join(f, matchers, "or"),
+            Self::Not(matcher) => write!(f, "not {matcher}"),
+        }
+    }
+}
+
+/// What is known about a device before it is opened, as passed to an [`AccessPolicy`]
+///
+/// Requests are built by colpetto from udev when libinput opens a device. They can also be built by hand,
+/// e.g. to test a policy:
+///
+/// ```
+/// use colpetto::{AccessPolicy, AccessRequest, DeviceCapability, DeviceMatch};
+///
+/// let policy = AccessPolicy::allow_all().deny(DeviceMatch::property("ID_BUS", "usb"));
+///
+/// let request = AccessRequest::new("/dev/input/event3")
+///     .with_capabilities(DeviceCapability::Keyboard)
+///     .with_property("ID_BUS", "usb");
+///
+/// assert!(policy.check(&request).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct AccessRequest {
+    /// The path libinput asked to open
+    pub path: PathBuf,
+    /// The device name, from the parent input device
+    pub name: Option<String>,
+    /// The kernel ids of the device
+    pub input_id: Option<InputId>,
+    /// The capabilities the device is expected to have, derived from the udev classification.
+    /// These are only hints, libinput may end up using the device differently.
+    pub capabilities: Capabilities,
+    /// Every udev property of the device
+    pub udev_properties: HashMap<String, String>,
+}
+
+impl AccessRequest {
+    /// Creates a request for the device at `path`, with nothing else known about it
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            name: None,
+            input_id: None,
+            capabilities: Capabilities::empty(),
+            udev_properties: HashMap::new(),
+        }
+    }
+
+    /// Sets the device name
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets the kernel ids of the device
+    pub fn with_input_id(mut self, input_id: InputId) -> Self {
+        self.input_id = Some(input_id);
+        self
+    }
+
+    /// Sets the capabilities the device is expected to have
+    pub fn with_capabilities(mut self, capabilities: impl Into<Capabilities>) -> Self {
+        self.capabilities = capabilities.into();
+        self
+    }
+
+    /// Adds a udev property, replacing any previous value of `key`
+    pub fn with_property(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.udev_properties.insert(key.into(), value.into());
+        self
+    }
+
+    /// Gathers what udev knows about the device node at `path`.
+    /// Paths which are not device nodes known to udev only have their path set.
+    pub(crate) fn from_udev(udev: &Udev, path: &Path) -> Self {
+        let mut request = Self::new(path);
+
+        let Some(device) = rustix::fs::stat(path).ok().and_then(|stat| unsafe {
+            UdevDevice::from_raw(sys::udev_device_new_from_devnum(
+                udev.as_raw(),
+                b'c' as _,
+                stat.st_rdev as _,
+            ))
+        }) else {
+            return request;
+        };
+
+        request.udev_properties = device
+            .properties()
+            .map(|(key, value)| {
+                (
+                    key.to_string_lossy().into_owned(),
+                    value.to_string_lossy().into_owned(),
+                )
+            })
+            .collect();
+
+        let parent = device.parent(c"input");
+
+        request.name = parent
+            .as_ref()
+            .and_then(|parent| parent.sysattr(c"name"))
+            .map(|name| name.to_string_lossy().trim_ascii_end().to_owned());
+
+        let product = parent
+            .as_ref()
+            .and_then(|parent| Some(parent.property(c"PRODUCT")?.to_string_lossy().into_owned()));
+
+        let properties = DeviceProperties::from_properties(
+            request
+                .udev_properties
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_str()))
+                .chain(product.as_deref().map(|product| ("PRODUCT", product))),
+        );
+
+        request.input_id = properties.input_id;
+        request.capabilities = capability_hints(&properties);
+
+        request
+    }
+}
+
+/// Guesses the capabilities of a device from its udev classification
+fn capability_hints(properties: &DeviceProperties) -> Capabilities {
+    let class = &properties.classification;
+
+    [
+        (class.keyboard || class.key, DeviceCapability::Keyboard),
+        (
+            class.mouse || class.touchpad || class.pointingstick || class.trackball,
+            DeviceCapability::Pointer,
+        ),
+        (class.touchscreen, DeviceCapability::Touch),
+        (class.tablet, DeviceCapability::TabletTool),
+        (class.tablet_pad, DeviceCapability::TabletPad),
+        (class.touchpad, DeviceCapability::Gesture),
+        (class.switch, DeviceCapability::Switch),
+    ]
+    .into_iter()
+    .filter_map(|(has, capability)| has.then_some(capability))
+    .collect()
+}
+
+/// Matches `text` against a glob supporting `*` and `?`
+fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` in the pattern and the text position it is currently matching up to
+    let mut star = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == b'?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glob(pattern: &str, text: &str) -> bool {
+        glob_match(pattern.as_bytes(), text.as_bytes())
+    }
+
+    fn request(path: &str, name: &str, capabilities: Capabilities) -> AccessRequest {
+        AccessRequest::new(path)
+            .with_name(name)
+            .with_input_id(InputId {
+                bustype: 0x03,
+                vendor: 0x046d,
+                product: 0xc52b,
+                version: 0x111,
+            })
+            .with_capabilities(capabilities)
+            .with_property("ID_BUS", "usb")
+            .with_property("ID_PATH", "pci-0000:00:14.0-usb-0:3:1.0")
+    }
+
+    fn keyboard() -> AccessRequest {
+        request(
+            "/dev/input/event3",
+            "Logitech USB Keyboard",
+            DeviceCapability::Keyboard.into(),
+        )
+    }
+
+    #[test]
+    fn glob_literal() {
+        assert!(glob("event3", "event3"));
+        assert!(!glob("event3", "event30"));
+        assert!(!glob("event30", "event3"));
+    }
+
+    #[test]
+    fn glob_empty_pattern() {
+        assert!(glob("", ""));
+        assert!(!glob("", "event3"));
+        assert!(glob("*", ""));
+        assert!(!glob("?", ""));
+    }
+
+    #[test]
+    fn glob_star() {
+        assert!(glob("*", "anything at all"));
+        assert!(glob("/dev/input/event*", "/dev/input/event"));
+        assert!(glob("/dev/input/event*", "/dev/input/event12"));
+        assert!(!glob("/dev/input/event*", "/dev/input/mouse0"));
+        assert!(glob("*Keyboard", "Logitech USB Keyboard"));
+        assert!(glob("*USB*", "Logitech USB Keyboard"));
+        assert!(glob("a*b*c", "aXbYbZc"));
+        assert!(!glob("a*b*c", "aXbYbZ"));
+        assert!(glob("**", "x"));
+    }
+
+    #[test]
+    fn glob_trailing_star_after_partial_match() {
+        assert!(glob("pci-*-usb-0:3*", "pci-0000:00:14.0-usb-0:3:1.0"));
+        assert!(!glob("pci-*-usb-0:3*", "pci-0000:00:14.0-usb-0:2:1.0"));
+        assert!(glob("ab*", "ab"));
+        assert!(!glob("ab*", "a"));
+    }
+
+    #[test]
+    fn glob_question_mark() {
+        assert!(glob("event?", "event3"));
+        assert!(!glob("event?", "event"));
+        assert!(!glob("event?", "event12"));
+        assert!(glob("event??", "event12"));
+        assert!(glob("?*", "x"));
+    }
+
+    #[test]
+    fn device_match() {
+        let request = keyboard();
+
+        assert!(DeviceMatch::Any.matches(&request));
+        assert!(DeviceMatch::path("/dev/input/event?").matches(&request));
+        assert!(DeviceMatch::name("Logitech*").matches(&request));
+        assert!(DeviceMatch::property("ID_BUS", "usb").matches(&request));
+        assert!(!DeviceMatch::property("ID_SERIAL", "*").matches(&request));
+        assert!(DeviceMatch::capability(DeviceCapability::Keyboard).matches(&request));
+        assert!(!DeviceMatch::capability(DeviceCapability::Pointer).matches(&request));
+        assert!(DeviceMatch::vendor(0x046d).matches(&request));
+        assert!(DeviceMatch::vendor_product(0x046d, 0xc52b).matches(&request));
+        assert!(!DeviceMatch::vendor_product(0x046d, 0xc52c).matches(&request));
+
+        let usb_keyboard = DeviceMatch::property("ID_BUS", "usb")
+            .and(DeviceMatch::capability(DeviceCapability::Keyboard));
+        assert!(usb_keyboard.matches(&request));
+        assert!(!(!usb_keyboard).matches(&request));
+        assert!(
+            DeviceMatch::name("Mouse")
+                .or(DeviceMatch::vendor(0x046d))
+                .matches(&request)
+        );
+    }
+
+    #[test]
+    fn missing_name_never_matches() {
+        let request = AccessRequest {
+            name: None,
+            ..keyboard()
+        };
+
+        assert!(!DeviceMatch::name("*").matches(&request));
+    }
+
+    #[test]
+    fn default_access() {
+        assert_eq!(AccessPolicy::allow_all().decide(&keyboard()), Access::Allow);
+        assert_eq!(AccessPolicy::deny_all().decide(&keyboard()), Access::Deny);
+        assert_eq!(
+            AccessPolicy::deny_all().check(&keyboard()),
+            Err(AccessDenied {
+                reason: "matches no allow rule".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn allowlist() {
+        let policy =
+            AccessPolicy::deny_all().allow(DeviceMatch::capability(DeviceCapability::Pointer));
+        let mouse = request(
+            "/dev/input/event4",
+            "Logitech USB Mouse",
+            DeviceCapability::Pointer.into(),
+        );
+
+        assert_eq!(policy.decide(&mouse), Access::Allow);
+        assert_eq!(policy.decide(&keyboard()), Access::Deny);
+    }
+
+    #[test]
+    fn denylist() {
+        let policy = AccessPolicy::allow_all().deny(DeviceMatch::name("*Keyboard"));
+        let mouse = request(
+            "/dev/input/event4",
+            "Logitech USB Mouse",
+            DeviceCapability::Pointer.into(),
+        );
+
+        assert_eq!(policy.decide(&mouse), Access::Allow);
+        assert_eq!(
+            policy.check(&keyboard()),
+            Err(AccessDenied {
+                reason: "matches deny rule #0: name=\"*Keyboard\"".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let keyboards = DeviceMatch::capability(DeviceCapability::Keyboard);
+        let logitech = DeviceMatch::vendor(0x046d);
+
+        let policy = AccessPolicy::deny_all()
+            .allow(keyboards.clone())
+            .deny(logitech.clone());
+        assert_eq!(policy.decide(&keyboard()), Access::Allow);
+
+        let policy = AccessPolicy::allow_all().deny(logitech).allow(keyboards);
+        assert_eq!(
+            policy.check(&keyboard()),
+            Err(AccessDenied {
+                reason: "matches deny rule #0: vendor=046d".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn capability_hints_from_classification() {
+        let properties = DeviceProperties::from_properties([
+            ("ID_INPUT", "1"),
+            ("ID_INPUT_TOUCHPAD", "1"),
+            ("ID_INPUT_KEY", "1"),
+        ]);
+
+        assert_eq!(
+            capability_hints(&properties),
+            DeviceCapability::Keyboard | DeviceCapability::Pointer | DeviceCapability::Gesture
+        );
+        assert!(capability_hints(&DeviceProperties::default()).is_empty());
+    }
+
+    #[test]
+    fn built_requests() {
+        let request = AccessRequest::new("/dev/input/event7");
+        assert_eq!(request.path, PathBuf::from("/dev/input/event7"));
+        assert_eq!(request.name, None);
+        assert_eq!(request.input_id, None);
+        assert!(request.capabilities.is_empty());
+        assert!(request.udev_properties.is_empty());
+
+        let request = request
+            .with_property("ID_BUS", "usb")
+            .with_property("ID_BUS", "bluetooth");
+        assert_eq!(request.udev_properties.len(), 1);
+        assert!(DeviceMatch::property("ID_BUS", "bluetooth").matches(&request));
+        assert!(!DeviceMatch::name("*").matches(&request));
+    }
+}
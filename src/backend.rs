@@ -0,0 +1,76 @@
+//! Abstracting over where events come from, so code consuming them can be tested without devices.
+//!
+//! Libinput types call into libinput directly, code using them can only run on a machine with input devices
+//! and access to them. An [`InputBackend`] is instead a stream of [owned events](crate::event::owned) that can
+//! also configure and suspend the devices it reports, which is all most consumers need.
+//!
+//! Available backends are:
+//! - [`LibinputBackend`], reading events from a libinput context. Requires the `tokio` feature.
+//! - [`FakeBackend`], an in-memory backend driven by the test, which records the configuration applied to its devices.
+//!
+//! # Example usage
+//!
+//! ```
+//! use colpetto::{
+//!     DeviceCapability, DeviceConfig, OwnedEvent,
+//!     backend::{FakeBackend, FakeDevice, InputBackend},
+//!     event::KeyState,
+//! };
+//! use tokio_stream::StreamExt;
+//!
+//! async fn make_left_handed(backend: &mut impl InputBackend) -> colpetto::Result<()> {
+//!     while let Some(event) = backend.try_next().await? {
+//!         if let OwnedEvent::Device(_) = event {
+//!             let _ = backend.apply_config(&event.device().sysname, &DeviceConfig::new().left_handed(true));
+//!         }
+//!     }
+//!
+//!     Ok(())
+//! }
+//!
+//! let fake = FakeBackend::new();
+//! let keyboard = fake.add_device(FakeDevice::new("Fake Keyboard").capabilities(DeviceCapability::Keyboard));
+//! fake.key(&keyboard, 30, KeyState::Pressed);
+//! ```
+
+use futures_core::Stream;
+
+use crate::{ConfigError, DeviceConfig, Error, OwnedEvent, Result, event::DeviceInfo};
+
+mod fake;
+#[cfg(feature = "tokio")]
+mod libinput;
+
+pub use fake::{ConfigCall, FakeBackend, FakeDevice};
+#[cfg(feature = "tokio")]
+pub use libinput::LibinputBackend;
+
+/// A source of events and the devices they come from, see the [module documentation](self)
+pub trait InputBackend: Stream<Item = Result<OwnedEvent>> + Unpin {
+    /// Returns the devices currently present
+    fn devices(&self) -> Vec<DeviceInfo>;
+
+    /// Applies `config` to the device with the given sysname, e.g. `event5`.
+    /// See [`Device::apply_config`](crate::Device::apply_config).
+    fn apply_config(&self, sysname: &str, config: &DeviceConfig) -> Result<(), BackendError>;
+
+    /// Suspends the backend, removing every device
+    fn suspend(&self);
+
+    /// Resumes the backend, adding back the devices still present
+    fn resume(&self) -> Result<()>;
+}
+
+/// Error returned by [`InputBackend::apply_config`]
+#[derive(Debug, thiserror::Error)]
+pub enum BackendError {
+    /// No device with the given sysname is present
+    #[error("No device named {0}")]
+    UnknownDevice(String),
+    /// The device refused the configuration
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    /// The backend failed to carry out the request
+    #[error(transparent)]
+    Libinput(#[from] Error),
+}
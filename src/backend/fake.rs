@@ -0,0 +1,798 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard},
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+use futures_core::Stream;
+
+use crate::{
+    Capabilities, ConfigError, DeviceConfig, DeviceId, Result,
+    backend::{BackendError, InputBackend},
+    event::{
+        AxisValues, ButtonState, DeviceInfo, KeyState, OwnedDeviceAddedEvent, OwnedDeviceEvent,
+        OwnedDeviceRemovedEvent, OwnedEvent, OwnedKeyboardEvent, OwnedKeyboardKeyEvent,
+        OwnedPointerButtonEvent, OwnedPointerEvent, OwnedPointerMotionEvent,
+        OwnedPointerScrollWheelEvent, OwnedTouchEvent, OwnedTouchFrameEvent, OwnedTouchPoint,
+        OwnedTouchSlot,
+    },
+};
+
+/// `BUS_VIRTUAL` from `linux/input.h`
+const BUS_VIRTUAL: u32 = 0x06;
+
+/// A device to add to a [`FakeBackend`]
+#[derive(Debug, Clone)]
+pub struct FakeDevice {
+    name: String,
+    sysname: Option<String>,
+    capabilities: Capabilities,
+    vendor_id: u32,
+    product_id: u32,
+    size: Option<(f64, f64)>,
+    config_error: Option<ConfigError>,
+}
+
+impl FakeDevice {
+    /// Creates a device without capabilities, named `name`
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            sysname: None,
+            capabilities: Capabilities::empty(),
+            vendor_id: 0,
+            product_id: 0,
+            size: None,
+            config_error: None,
+        }
+    }
+
+    /// Sets the sysname, `eventN` by default with `N` counting up from 0
+    pub fn sysname(mut self, sysname: impl Into<String>) -> Self {
+        self.sysname = Some(sysname.into());
+        self
+    }
+
+    /// Sets the capabilities
+    pub fn capabilities(mut self, capabilities: impl Into<Capabilities>) -> Self {
+        self.capabilities = capabilities.into();
+        self
+    }
+
+    /// Sets the vendor and product ids, 0 by default
+    pub fn ids(mut self, vendor_id: u32, product_id: u32) -> Self {
+        self.vendor_id = vendor_id;
+        self.product_id = product_id;
+        self
+    }
+
+    /// Sets the physical size in mm, for absolute devices
+    pub fn size(mut self, width: f64, height: f64) -> Self {
+        self.size = Some((width, height));
+        self
+    }
+
+    /// Makes the device refuse every configuration with `error`. The calls are still recorded.
+    pub fn config_error(mut self, error: ConfigError) -> Self {
+        self.config_error = Some(error);
+        self
+    }
+}
+
+/// A configuration applied to a device of a [`FakeBackend`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ConfigCall {
+    /// The sysname passed to [`InputBackend::apply_config`]
+    pub sysname: String,
+    /// The configuration passed to [`InputBackend::apply_config`]
+    pub config: DeviceConfig,
+}
+
+/// An in-memory backend driven by the test using it.
+///
+/// Devices are added and removed on demand, and input events are synthesized for them. Events are stamped with
+/// a fake clock starting at 0, which only moves with [`advance`](Self::advance). Every configuration applied through
+/// [`InputBackend::apply_config`] is recorded, and kept as the current configuration of the device if accepted.
+///
+/// Like a libinput context, suspending the backend removes every device and resuming adds them back, input events
+/// synthesized in between are dropped. Keys and buttons held when suspending are no longer counted as pressed.
+///
+/// The fake is a cheap handle, clones share the same state, so a test can keep one to drive the backend while another
+/// is owned by the code under test. Handles can be sent to other threads.
+///
+/// # Panics
+///
+/// Methods taking a sysname panic if no device with that sysname was added, to catch mistakes in the test itself.
+#[derive(Debug, Clone, Default)]
+pub struct FakeBackend {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    devices: Vec<FakeEntry>,
+    /// Counts up to name devices without an explicit sysname
+    next_sysname: u32,
+    events: VecDeque<OwnedEvent>,
+    waker: Option<Waker>,
+    config_calls: Vec<ConfigCall>,
+    time_usec: u64,
+    suspended: bool,
+    /// Seat wide pressed count of every key
+    keys: HashMap<u32, u32>,
+    /// Seat wide pressed count of every button
+    buttons: HashMap<u32, u32>,
+}
+
+#[derive(Debug)]
+struct FakeEntry {
+    info: Arc<DeviceInfo>,
+    config: DeviceConfig,
+    config_error: Option<ConfigError>,
+}
+
+impl State {
+    fn device(&self, sysname: &str) -> &FakeEntry {
+        self.devices
+            .iter()
+            .find(|entry| entry.info.sysname == sysname)
+            .unwrap_or_else(|| panic!("no fake device named {sysname}"))
+    }
+
+    fn push(&mut self, event: OwnedEvent) {
+        self.events.push_back(event);
+
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+
+    /// Returns the device to attach to an input event from `sysname`, `None` while suspended as the event is dropped
+    fn input_device(&self, sysname: &str) -> Option<Arc<DeviceInfo>> {
+        let device = self.device(sysname).info.clone();
+
+        (!self.suspended).then_some(device)
+    }
+
+    /// Queues an input event from `sysname`, unless suspended
+    fn input(&mut self, sysname: &str, event: impl FnOnce(Arc<DeviceInfo>, u64) -> OwnedEvent) {
+        if let Some(device) = self.input_device(sysname) {
+            let event = event(device, self.time_usec);
+            self.push(event);
+        }
+    }
+}
+
+/// Updates a seat wide pressed count, returning the new count
+fn press(counts: &mut HashMap<u32, u32>, code: u32, pressed: bool) -> u32 {
+    let count = counts.entry(code).or_default();
+
+    *count = match pressed {
+        true => *count + 1,
+        false => count.saturating_sub(1),
+    };
+
+    *count
+}
+
+fn added(device: Arc<DeviceInfo>) -> OwnedEvent {
+    OwnedEvent::Device(OwnedDeviceEvent::Added(OwnedDeviceAddedEvent { device }))
+}
+
+fn removed(device: Arc<DeviceInfo>) -> OwnedEvent {
+    OwnedEvent::Device(OwnedDeviceEvent::Removed(OwnedDeviceRemovedEvent {
+        device,
+    }))
+}
+
+impl FakeBackend {
+    /// Creates a backend without devices
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        // The state stays consistent even if a test panicked while holding the lock
+        self.state
+            .lock()
+            .unwrap_or_else(|poison| poison.into_inner())
+    }
+
+    /// Adds a device and queues its added event, returning its sysname
+    ///
+    /// # Panics
+    ///
+    /// Panics if a device with the same sysname is already present
+    pub fn add_device(&self, device: FakeDevice) -> String {
+        let mut state = self.state();
+
+        let sysname = device.sysname.unwrap_or_else(|| {
+            state.next_sysname += 1;
+            format!("event{}", state.next_sysname - 1)
+        });
+
+        assert!(
+            state
+                .devices
+                .iter()
+                .all(|entry| entry.info.sysname != sysname),
+            "fake device {sysname} already present"
+        );
+
+        let info = Arc::new(DeviceInfo {
+            sysname: sysname.clone(),
+            name: device.name.clone(),
+            vendor_id: device.vendor_id,
+            product_id: device.product_id,
+            output_name: None,
+            physical_seat: "seat0".to_owned(),
+            logical_seat: "default".to_owned(),
+            capabilities: device.capabilities,
+            size: device.size,
+            id: DeviceId {
+                bustype: BUS_VIRTUAL,
+                vendor: device.vendor_id,
+                product: device.product_id,
+                name: device.name,
+                id_path: None,
+                id_serial: None,
+            },
+        });
+
+        if !state.suspended {
+            state.push(added(info.clone()));
+        }
+
+        state.devices.push(FakeEntry {
+            info,
+            config: DeviceConfig::default(),
+            config_error: device.config_error,
+        });
+
+        sysname
+    }
+
+    /// Removes a device and queues its removed event
+    pub fn remove_device(&self, sysname: &str) {
+        let mut state = self.state();
+
+        let index = state
+            .devices
+            .iter()
+            .position(|entry| entry.info.sysname == sysname)
+            .unwrap_or_else(|| panic!("no fake device named {sysname}"));
+
+        let entry = state.devices.remove(index);
+
+        if !state.suspended {
+            state.push(removed(entry.info));
+        }
+    }
+
+    /// Moves the fake clock forward, events synthesized afterwards are stamped with the new time
+    pub fn advance(&self, duration: Duration) {
+        self.state().time_usec += duration.as_micros() as u64;
+    }
+
+    /// Queues a key event
+    pub fn key(&self, sysname: &str, key: u32, key_state: KeyState) {
+        let mut state = self.state();
+
+        // Dropped events do not count as presses
+        let Some(device) = state.input_device(sysname) else {
+            return;
+        };
+
+        let seat_key_count = press(&mut state.keys, key, key_state == KeyState::Pressed);
+        let time_usec = state.time_usec;
+
+        state.push(OwnedEvent::Keyboard(OwnedKeyboardEvent::Key(
+            OwnedKeyboardKeyEvent {
+                device,
+                time_usec,
+                key,
+                key_state,
+                seat_key_count,
+            },
+        )));
+    }
+
+    /// Queues a relative pointer motion event, without acceleration
+    pub fn pointer_motion(&self, sysname: &str, dx: f64, dy: f64) {
+        self.state().input(sysname, |device, time_usec| {
+            OwnedEvent::Pointer(OwnedPointerEvent::Motion(OwnedPointerMotionEvent {
+                device,
+                time_usec,
+                dx,
+                dy,
+                dx_unaccelerated: dx,
+                dy_unaccelerated: dy,
+            }))
+        });
+    }
+
+    /// Queues a pointer button event
+    pub fn pointer_button(&self, sysname: &str, button: u32, button_state: ButtonState) {
+        let mut state = self.state();
+
+        // Dropped events do not count as presses
+        let Some(device) = state.input_device(sysname) else {
+            return;
+        };
+
+        let seat_button_count = press(
+            &mut state.buttons,
+            button,
+            button_state == ButtonState::Pressed,
+        );
+        let time_usec = state.time_usec;
+
+        state.push(OwnedEvent::Pointer(OwnedPointerEvent::Button(
+            OwnedPointerButtonEvent {
+                device,
+                time_usec,
+                button,
+                button_state,
+                seat_button_count,
+            },
+        )));
+    }
+
+    /// Queues a scroll wheel event, with values in degrees where one detent is 15 degrees
+    pub fn scroll_wheel(&self, sysname: &str, values: AxisValues) {
+        let v120 = |value: Option<f64>| value.map(|value| value / 15.0 * 120.0);
+
+        self.state().input(sysname, |device, time_usec| {
+            OwnedEvent::Pointer(OwnedPointerEvent::ScrollWheel(
+                OwnedPointerScrollWheelEvent {
+                    device,
+                    time_usec,
+                    values,
+                    values_v120: AxisValues {
+                        vertical: v120(values.vertical),
+                        horizontal: v120(values.horizontal),
+                    },
+                },
+            ))
+        });
+    }
+
+    /// Queues a touch down event, with coordinates in mm. The seat slot is the same as the slot.
+    pub fn touch_down(&self, sysname: &str, slot: i32, x: f64, y: f64) {
+        self.touch_point(sysname, slot, x, y, OwnedTouchEvent::Down);
+    }
+
+    /// Queues a touch motion event, with coordinates in mm. The seat slot is the same as the slot.
+    pub fn touch_motion(&self, sysname: &str, slot: i32, x: f64, y: f64) {
+        self.touch_point(sysname, slot, x, y, OwnedTouchEvent::Motion);
+    }
+
+    /// Queues a touch up event. The seat slot is the same as the slot.
+    pub fn touch_up(&self, sysname: &str, slot: i32) {
+        self.state().input(sysname, |device, time_usec| {
+            OwnedEvent::Touch(OwnedTouchEvent::Up(OwnedTouchSlot {
+                device,
+                time_usec,
+                slot,
+                seat_slot: slot,
+            }))
+        });
+    }
+
+    /// Queues a touch frame event, ending a set of touch events
+    pub fn touch_frame(&self, sysname: &str) {
+        self.state().input(sysname, |device, time_usec| {
+            OwnedEvent::Touch(OwnedTouchEvent::Frame(OwnedTouchFrameEvent {
+                device,
+                time_usec,
+            }))
+        });
+    }
+
+    fn touch_point(
+        &self,
+        sysname: &str,
+        slot: i32,
+        x: f64,
+        y: f64,
+        variant: fn(OwnedTouchPoint) -> OwnedTouchEvent,
+    ) {
+        self.state().input(sysname, |device, time_usec| {
+            OwnedEvent::Touch(variant(OwnedTouchPoint {
+                device,
+                time_usec,
+                slot,
+                seat_slot: slot,
+                x,
+                y,
+            }))
+        });
+    }
+
+    /// Queues an event as is, e.g. one read from a [recording](crate::record), even while suspended
+    pub fn push_event(&self, event: OwnedEvent) {
+        self.state().push(event);
+    }
+
+    /// Returns every configuration applied so far, in order, including the refused ones
+    pub fn config_calls(&self) -> Vec<ConfigCall> {
+        self.state().config_calls.clone()
+    }
+
+    /// Returns the options accepted so far by a device, merged together
+    pub fn current_config(&self, sysname: &str) -> DeviceConfig {
+        self.state().device(sysname).config.clone()
+    }
+
+    /// Returns whether the backend is suspended
+    pub fn is_suspended(&self) -> bool {
+        self.state().suspended
+    }
+
+    /// Returns how many events are queued and not yet yielded
+    pub fn pending_events(&self) -> usize {
+        self.state().events.len()
+    }
+}
+
+impl Stream for FakeBackend {
+    type Item = Result<OwnedEvent>;
+
+    /// Never ends, like an [`EventStream`](crate::EventStream)
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut state = self.state();
+
+        match state.events.pop_front() {
+            Some(event) => Poll::Ready(Some(Ok(event))),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl InputBackend for FakeBackend {
+    fn devices(&self) -> Vec<DeviceInfo> {
+        let state = self.state();
+
+        match state.suspended {
+            true => Vec::new(),
+            false => state
+                .devices
+                .iter()
+                .map(|entry| DeviceInfo::clone(&entry.info))
+                .collect(),
+        }
+    }
+
+    fn apply_config(&self, sysname: &str, config: &DeviceConfig) -> Result<(), BackendError> {
+        let mut state = self.state();
+
+        state.config_calls.push(ConfigCall {
+            sysname: sysname.to_owned(),
+            config: config.clone(),
+        });
+
+        let unknown = || BackendError::UnknownDevice(sysname.to_owned());
+
+        // Suspended backends have no devices
+        if state.suspended {
+            return Err(unknown());
+        }
+
+        let entry = state
+            .devices
+            .iter_mut()
+            .find(|entry| entry.info.sysname == sysname)
+            .ok_or_else(unknown)?;
+
+        if let Some(error) = entry.config_error {
+            return Err(error.into());
+        }
+
+        if let Some(enabled) = config.left_handed {
+            entry.config.left_handed = Some(enabled);
+        }
+
+        if let Some(degrees_cw) = config.rotation {
+            entry.config.rotation = Some(degrees_cw);
+        }
+
+        Ok(())
+    }
+
+    fn suspend(&self) {
+        let mut state = self.state();
+
+        if state.suspended {
+            return;
+        }
+
+        state.suspended = true;
+
+        // libinput releases everything held when it closes the devices, presses do not survive a resume
+        state.keys.clear();
+        state.buttons.clear();
+
+        let devices: Vec<_> = state
+            .devices
+            .iter()
+            .map(|entry| entry.info.clone())
+            .collect();
+
+        for device in devices {
+            state.push(removed(device));
+        }
+    }
+
+    fn resume(&self) -> Result<()> {
+        let mut state = self.state();
+
+        if !state.suspended {
+            return Ok(());
+        }
+
+        state.suspended = false;
+
+        let devices: Vec<_> = state
+            .devices
+            .iter()
+            .map(|entry| entry.info.clone())
+            .collect();
+
+        for device in devices {
+            state.push(added(device));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::{self, AssertUnwindSafe};
+
+    use super::*;
+    use crate::DeviceCapability;
+
+    impl FakeBackend {
+        fn take_events(&self) -> Vec<OwnedEvent> {
+            self.state().events.drain(..).collect()
+        }
+    }
+
+    /// Summarizes events as their type and sysname
+    fn summary(events: &[OwnedEvent]) -> Vec<(&'static str, &str)> {
+        events
+            .iter()
+            .map(|event| (event.event_type(), event.device().sysname.as_str()))
+            .collect()
+    }
+
+    fn key_counts(events: &[OwnedEvent]) -> Vec<u32> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                OwnedEvent::Keyboard(OwnedKeyboardEvent::Key(key)) => Some(key.seat_key_count),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn keyboard() -> FakeDevice {
+        FakeDevice::new("Fake Keyboard").capabilities(DeviceCapability::Keyboard)
+    }
+
+    #[test]
+    fn added_and_removed() {
+        let fake = FakeBackend::new();
+
+        let first = fake.add_device(keyboard());
+        let second = fake.add_device(FakeDevice::new("Fake Mouse").ids(0x046d, 0xc077));
+        let named = fake.add_device(keyboard().sysname("event42"));
+        assert_eq!(
+            [&*first, &*second, &*named],
+            ["event0", "event1", "event42"]
+        );
+
+        fake.remove_device(&second);
+
+        assert_eq!(
+            summary(&fake.take_events()),
+            [
+                ("Device Added", "event0"),
+                ("Device Added", "event1"),
+                ("Device Added", "event42"),
+                ("Device Removed", "event1"),
+            ]
+        );
+
+        let devices = fake.devices();
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].name, "Fake Keyboard");
+        assert_eq!(devices[0].capabilities, DeviceCapability::Keyboard.into());
+        assert_eq!(devices[0].id.bustype, BUS_VIRTUAL);
+        assert_eq!(devices[1].sysname, "event42");
+    }
+
+    #[test]
+    #[should_panic(expected = "fake device event0 already present")]
+    fn duplicate_sysname_panics() {
+        let fake = FakeBackend::new();
+        fake.add_device(keyboard());
+        fake.add_device(keyboard().sysname("event0"));
+    }
+
+    #[test]
+    fn suspend_and_resume() {
+        let fake = FakeBackend::new();
+        let keyboard = fake.add_device(keyboard());
+        let mouse = fake.add_device(FakeDevice::new("Fake Mouse"));
+        fake.take_events();
+
+        fake.suspend();
+        fake.suspend();
+        assert!(fake.is_suspended());
+        assert!(fake.devices().is_empty());
+        assert_eq!(
+            summary(&fake.take_events()),
+            [("Device Removed", "event0"), ("Device Removed", "event1")]
+        );
+
+        // Input is dropped and hotplug is silent while suspended
+        fake.key(&keyboard, 30, KeyState::Pressed);
+        fake.pointer_motion(&mouse, 1.0, 0.0);
+        let touchscreen = fake.add_device(FakeDevice::new("Fake Touchscreen"));
+        fake.remove_device(&mouse);
+        assert_eq!(fake.pending_events(), 0);
+
+        fake.resume().unwrap();
+        fake.resume().unwrap();
+        assert!(!fake.is_suspended());
+        assert_eq!(
+            summary(&fake.take_events()),
+            [("Device Added", "event0"), ("Device Added", &touchscreen)]
+        );
+    }
+
+    #[test]
+    fn dropped_presses_are_not_counted() {
+        let fake = FakeBackend::new();
+        let keyboard = fake.add_device(keyboard());
+        let mouse = fake.add_device(FakeDevice::new("Fake Mouse"));
+
+        fake.suspend();
+        fake.key(&keyboard, 30, KeyState::Pressed);
+        fake.pointer_button(&mouse, 272, ButtonState::Pressed);
+        fake.resume().unwrap();
+        fake.take_events();
+
+        fake.key(&keyboard, 30, KeyState::Pressed);
+        fake.key(&keyboard, 30, KeyState::Pressed);
+        fake.key(&keyboard, 30, KeyState::Released);
+        assert_eq!(key_counts(&fake.take_events()), [1, 2, 1]);
+
+        fake.pointer_button(&mouse, 272, ButtonState::Pressed);
+        match fake.take_events().as_slice() {
+            [OwnedEvent::Pointer(OwnedPointerEvent::Button(button))] => {
+                assert_eq!(button.seat_button_count, 1)
+            }
+            events => panic!("unexpected events {events:?}"),
+        }
+    }
+
+    #[test]
+    fn suspend_forgets_held_presses() {
+        let fake = FakeBackend::new();
+        let keyboard = fake.add_device(keyboard());
+        let mouse = fake.add_device(FakeDevice::new("Fake Mouse"));
+
+        fake.key(&keyboard, 30, KeyState::Pressed);
+        fake.key(&keyboard, 31, KeyState::Pressed);
+        fake.pointer_button(&mouse, 272, ButtonState::Pressed);
+
+        fake.suspend();
+        fake.resume().unwrap();
+        fake.take_events();
+
+        fake.key(&keyboard, 30, KeyState::Pressed);
+        fake.key(&keyboard, 31, KeyState::Released);
+        fake.pointer_button(&mouse, 272, ButtonState::Pressed);
+
+        let events = fake.take_events();
+        assert_eq!(key_counts(&events), [1, 0]);
+        match &events[2] {
+            OwnedEvent::Pointer(OwnedPointerEvent::Button(button)) => {
+                assert_eq!(button.seat_button_count, 1)
+            }
+            event => panic!("unexpected event {event:?}"),
+        }
+    }
+
+    #[test]
+    fn unknown_sysname_panics_without_counting() {
+        let fake = FakeBackend::new();
+        let keyboard = fake.add_device(keyboard());
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            fake.key("event9", 30, KeyState::Pressed)
+        }));
+        assert!(result.is_err());
+
+        fake.key(&keyboard, 30, KeyState::Pressed);
+        let events = fake.take_events();
+        assert_eq!(key_counts(&events), [1]);
+    }
+
+    #[test]
+    fn clock() {
+        let fake = FakeBackend::new();
+        let keyboard = fake.add_device(keyboard());
+
+        fake.key(&keyboard, 30, KeyState::Pressed);
+        fake.advance(Duration::from_millis(8));
+        fake.key(&keyboard, 30, KeyState::Released);
+
+        let times: Vec<_> = fake
+            .take_events()
+            .iter()
+            .map(OwnedEvent::time_usec)
+            .collect();
+        assert_eq!(times, [None, Some(0), Some(8_000)]);
+    }
+
+    #[test]
+    fn config_calls_are_recorded() {
+        let fake = FakeBackend::new();
+        let keyboard = fake.add_device(keyboard());
+        let stubborn = fake.add_device(
+            FakeDevice::new("Stubborn Keyboard").config_error(ConfigError::Unsupported),
+        );
+
+        let left_handed = DeviceConfig::new().left_handed(true);
+        let rotated = DeviceConfig::new().rotation(90);
+
+        assert!(fake.apply_config(&keyboard, &left_handed).is_ok());
+        assert!(fake.apply_config(&keyboard, &rotated).is_ok());
+        assert!(matches!(
+            fake.apply_config(&stubborn, &rotated),
+            Err(BackendError::Config(ConfigError::Unsupported))
+        ));
+        assert!(matches!(
+            fake.apply_config("event9", &rotated),
+            Err(BackendError::UnknownDevice(sysname)) if sysname == "event9"
+        ));
+
+        fake.suspend();
+        assert!(matches!(
+            fake.apply_config(&keyboard, &rotated),
+            Err(BackendError::UnknownDevice(_))
+        ));
+
+        let calls: Vec<_> = fake
+            .config_calls()
+            .into_iter()
+            .map(|call| (call.sysname, call.config))
+            .collect();
+        assert_eq!(
+            calls,
+            [
+                (keyboard.clone(), left_handed),
+                (keyboard.clone(), rotated.clone()),
+                (stubborn.clone(), rotated.clone()),
+                ("event9".to_owned(), rotated.clone()),
+                (keyboard.clone(), rotated),
+            ]
+        );
+
+        assert_eq!(
+            fake.current_config(&keyboard),
+            DeviceConfig::new().left_handed(true).rotation(90)
+        );
+        assert_eq!(fake.current_config(&stubborn), DeviceConfig::new());
+    }
+}
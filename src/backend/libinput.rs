@@ -0,0 +1,80 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_core::Stream;
+
+use crate::{
+    DeviceConfig, EventStream, Libinput, OwnedEvent, Result,
+    backend::{BackendError, InputBackend},
+    event::DeviceInfo,
+};
+
+/// A backend reading events from a libinput context, converted with [`Event::to_owned`](crate::Event::to_owned)
+#[derive(Debug)]
+pub struct LibinputBackend {
+    libinput: Libinput,
+    stream: EventStream,
+}
+
+impl LibinputBackend {
+    /// Streams the events of `libinput`, which should already be assigned to a seat or have devices added.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio context
+    pub fn new(libinput: Libinput) -> Result<Self> {
+        let stream = libinput.event_stream()?;
+
+        Ok(Self { libinput, stream })
+    }
+
+    /// Returns the underlying context
+    pub fn libinput(&self) -> &Libinput {
+        &self.libinput
+    }
+}
+
+impl Stream for LibinputBackend {
+    type Item = Result<OwnedEvent>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.stream)
+            .poll_next(cx)
+            .map(|item| item.map(|event| event.map(|event| event.to_owned())))
+    }
+}
+
+impl InputBackend for LibinputBackend {
+    fn devices(&self) -> Vec<DeviceInfo> {
+        self.libinput
+            .handler()
+            .devices
+            .devices()
+            .iter()
+            .map(DeviceInfo::new)
+            .collect()
+    }
+
+    fn apply_config(&self, sysname: &str, config: &DeviceConfig) -> Result<(), BackendError> {
+        let device = self
+            .libinput
+            .handler()
+            .devices
+            .devices()
+            .into_iter()
+            .find(|device| device.sysname().to_bytes() == sysname.as_bytes())
+            .ok_or_else(|| BackendError::UnknownDevice(sysname.to_owned()))?;
+
+        Ok(device.apply_config(config)?)
+    }
+
+    fn suspend(&self) {
+        self.libinput.suspend();
+    }
+
+    fn resume(&self) -> Result<()> {
+        self.libinput.resume()
+    }
+}
@@ -0,0 +1,298 @@
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Sub, SubAssign};
+
+use crate::DeviceCapability;
+
+/// A set of [`DeviceCapability`] values, stored as a bitflag set.
+///
+/// Returned by [`Device::capabilities`](crate::Device::capabilities), sets can be combined
+/// with the usual bitwise operators and iterated in a stable order.
+///
+/// # Example usage
+///
+/// ```
+/// use colpetto::{Capabilities, DeviceCapability};
+///
+/// let wanted = DeviceCapability::Pointer | DeviceCapability::Gesture;
+///
+/// if device.capabilities().contains_all(wanted) {
+///     println!("{} is a touchpad: {}", device, device.capabilities());
+/// }
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Capabilities {
+    bits: u32,
+}
+
+impl Capabilities {
+    /// Returns an empty set
+    pub const fn empty() -> Self {
+        Self { bits: 0 }
+    }
+
+    /// Returns a set containing every known capability
+    pub const fn all() -> Self {
+        let mut bits = 0;
+        let mut i = 0;
+
+        while i < DeviceCapability::ALL.len() {
+            bits |= Self::bit(DeviceCapability::ALL[i]);
+            i += 1;
+        }
+
+        Self { bits }
+    }
+
+    /// Builds a set from its raw bits, where bit `n` is the libinput capability with value `n`.
+    /// Unknown bits are dropped.
+    pub const fn from_bits_truncate(bits: u32) -> Self {
+        Self {
+            bits: bits & Self::all().bits,
+        }
+    }
+
+    /// Returns the raw bits of this set, see [`from_bits_truncate`](Self::from_bits_truncate)
+    pub const fn bits(self) -> u32 {
+        self.bits
+    }
+
+    const fn bit(capability: DeviceCapability) -> u32 {
+        1 << capability as u32
+    }
+
+    /// Returns `true` if the set contains no capabilities
+    pub const fn is_empty(self) -> bool {
+        self.bits == 0
+    }
+
+    /// Returns the number of capabilities in the set
+    pub const fn len(self) -> usize {
+        self.bits.count_ones() as usize
+    }
+
+    /// Returns `true` if the set contains `capability`
+    pub const fn contains(self, capability: DeviceCapability) -> bool {
+        self.bits & Self::bit(capability) != 0
+    }
+
+    /// Returns `true` if every capability in `other` is also in this set
+    pub const fn contains_all(self, other: Self) -> bool {
+        self.bits & other.bits == other.bits
+    }
+
+    /// Returns `true` if this set and `other` have at least one capability in common
+    pub const fn intersects(self, other: Self) -> bool {
+        self.bits & other.bits != 0
+    }
+
+    /// Adds `capability` to the set
+    pub fn insert(&mut self, capability: DeviceCapability) {
+        self.bits |= Self::bit(capability);
+    }
+
+    /// Removes `capability` from the set
+    pub fn remove(&mut self, capability: DeviceCapability) {
+        self.bits &= !Self::bit(capability);
+    }
+
+    /// Iterates over the capabilities in the set, in the order of [`DeviceCapability::ALL`]
+    pub fn iter(self) -> CapabilitiesIter {
+        CapabilitiesIter {
+            set: self,
+            index: 0,
+        }
+    }
+}
+
+impl From<DeviceCapability> for Capabilities {
+    fn from(capability: DeviceCapability) -> Self {
+        Self {
+            bits: Self::bit(capability),
+        }
+    }
+}
+
+impl FromIterator<DeviceCapability> for Capabilities {
+    fn from_iter<T: IntoIterator<Item = DeviceCapability>>(iter: T) -> Self {
+        let mut set = Self::empty();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<DeviceCapability> for Capabilities {
+    fn extend<T: IntoIterator<Item = DeviceCapability>>(&mut self, iter: T) {
+        iter.into_iter()
+            .for_each(|capability| self.insert(capability));
+    }
+}
+
+impl IntoIterator for Capabilities {
+    type Item = DeviceCapability;
+    type IntoIter = CapabilitiesIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the capabilities in a [`Capabilities`] set
+#[derive(Debug, Clone)]
+pub struct CapabilitiesIter {
+    set: Capabilities,
+    index: usize,
+}
+
+impl Iterator for CapabilitiesIter {
+    type Item = DeviceCapability;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(&capability) = DeviceCapability::ALL.get(self.index) {
+            self.index += 1;
+
+            if self.set.contains(capability) {
+                return Some(capability);
+            }
+        }
+
+        None
+    }
+}
+
+macro_rules! impl_ops {
+    ($($trait:ident, $fn:ident, $assign_trait:ident, $assign_fn:ident, |$a:ident, $b:ident| $op:expr;)+) => {
+        $(
+            impl<T: Into<Capabilities>> $trait<T> for Capabilities {
+                type Output = Self;
+
+                fn $fn(self, rhs: T) -> Self {
+                    let ($a, $b) = (self.bits, rhs.into().bits);
+                    Self { bits: $op }
+                }
+            }
+
+            impl<T: Into<Capabilities>> $trait<T> for DeviceCapability {
+                type Output = Capabilities;
+
+                fn $fn(self, rhs: T) -> Capabilities {
+                    Capabilities::from(self).$fn(rhs)
+                }
+            }
+
+            impl<T: Into<Capabilities>> $assign_trait<T> for Capabilities {
+                fn $assign_fn(&mut self, rhs: T) {
+                    *self = (*self).$fn(rhs);
+                }
+            }
+        )+
+    };
+}
+
+impl_ops! {
+    BitOr, bitor, BitOrAssign, bitor_assign, |a, b| a | b;
+    BitAnd, bitand, BitAndAssign, bitand_assign, |a, b| a & b;
+    Sub, sub, SubAssign, sub_assign, |a, b| a & !b;
+}
+
+impl std::fmt::Debug for Capabilities {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// Formats as the space separated capability names, e.g. `keyboard pointer`
+impl std::fmt::Display for Capabilities {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, capability) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+
+            write!(f, "{capability}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use DeviceCapability::*;
+
+    #[test]
+    fn set_operations() {
+        let touchpad = Pointer | Gesture;
+        let keyboard = Capabilities::from(Keyboard);
+
+        assert_eq!(touchpad.len(), 2);
+        assert!(touchpad.contains(Pointer));
+        assert!(!touchpad.contains(Keyboard));
+        assert!(touchpad.contains_all(Gesture.into()));
+        assert!(!touchpad.contains_all(Gesture | Touch));
+        assert!(touchpad.intersects(Pointer | Keyboard));
+        assert!(!touchpad.intersects(keyboard));
+
+        assert_eq!(touchpad | keyboard, Keyboard | Pointer | Gesture);
+        assert_eq!(touchpad & (Pointer | Touch), Pointer.into());
+        assert_eq!(touchpad - Pointer, Gesture.into());
+        assert_eq!(touchpad - keyboard, touchpad);
+
+        let mut set = Capabilities::empty();
+        set |= Touch;
+        set.insert(Switch);
+        set.insert(Switch);
+        assert_eq!(set, Touch | Switch);
+        set -= Touch;
+        set.remove(Keyboard);
+        assert_eq!(set, Switch.into());
+        set &= keyboard;
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn all_and_bits() {
+        let all = Capabilities::all();
+
+        assert_eq!(all.len(), DeviceCapability::ALL.len());
+        assert_eq!(Capabilities::from_bits_truncate(u32::MAX), all);
+        assert_eq!(Capabilities::from_bits_truncate(all.bits()), all);
+        assert_eq!(
+            Capabilities::from_bits_truncate(1 << Pointer.as_raw()),
+            Pointer.into()
+        );
+        assert_eq!(
+            Capabilities::from_bits_truncate(1 << 31),
+            Capabilities::empty()
+        );
+    }
+
+    #[test]
+    fn iteration_order_is_stable() {
+        let set: Capabilities = [Switch, Touch, Pointer, Keyboard].into_iter().collect();
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            [Keyboard, Pointer, Touch, Switch]
+        );
+
+        assert_eq!(
+            Capabilities::all().into_iter().collect::<Vec<_>>(),
+            DeviceCapability::ALL
+        );
+        assert_eq!(Capabilities::empty().iter().next(), None);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!((Gesture | Pointer).to_string(), "pointer gesture");
+        assert_eq!(
+            Capabilities::all().to_string(),
+            "keyboard pointer touch tablet-tool tablet-pad gesture switch"
+        );
+        assert_eq!(Capabilities::empty().to_string(), "");
+        assert_eq!(
+            format!("{:?}", Keyboard | TabletPad),
+            "{Keyboard, TabletPad}"
+        );
+    }
+}
@@ -0,0 +1,58 @@
+use crate::sys;
+
+/// Error returned when libinput refuses a configuration change
+#[derive(Debug, Copy, Clone, Eq, PartialEq, thiserror::Error)]
+pub enum ConfigError {
+    /// The configuration option is not available on this device
+    #[error("Configuration not supported by this device")]
+    Unsupported,
+    /// The requested value is not valid for this device
+    #[error("Invalid configuration value")]
+    Invalid,
+}
+
+impl ConfigError {
+    /// Converts a raw libinput configuration status into a result
+    pub const fn from_status(status: sys::libinput_config_status) -> Result<(), Self> {
+        match status {
+            sys::libinput_config_status::LIBINPUT_CONFIG_STATUS_SUCCESS => Ok(()),
+            sys::libinput_config_status::LIBINPUT_CONFIG_STATUS_UNSUPPORTED => {
+                Err(Self::Unsupported)
+            }
+            _ => Err(Self::Invalid),
+        }
+    }
+}
+
+/// A set of configuration values that can be applied to a device or a whole [`DeviceGroup`](crate::DeviceGroup).
+///
+/// Every field is optional, `None` leaves the current value untouched.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[non_exhaustive]
+pub struct DeviceConfig {
+    /// Whether the device should be used in left-handed mode
+    pub left_handed: Option<bool>,
+    /// The clockwise rotation of the device in degrees
+    pub rotation: Option<u32>,
+}
+
+impl DeviceConfig {
+    /// Creates an empty configuration that leaves every option untouched
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the left-handed mode
+    pub fn left_handed(mut self, enabled: bool) -> Self {
+        self.left_handed = Some(enabled);
+        self
+    }
+
+    /// Sets the clockwise rotation in degrees
+    pub fn rotation(mut self, degrees_cw: u32) -> Self {
+        self.rotation = Some(degrees_cw);
+        self
+    }
+}
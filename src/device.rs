@@ -1,13 +1,27 @@
-use std::ffi::CStr;
+use std::{
+    ffi::{CStr, CString},
+    future::Future,
+    hash::{Hash, Hasher},
+    pin::Pin,
+    rc::{Rc, Weak},
+    task::{Context, Poll},
+};
 
-use crate::{DeviceGroup, Seat, macros, sys};
+use crate::{
+    Capabilities, ConfigError, DeviceConfig, DeviceGroup, DeviceId, DeviceProperties, Handler,
+    Seat, sys, tracker::DeviceTracker, udev::UdevDevice,
+};
 
 /// A base handle for accessing libinput devices.
 pub struct Device {
     raw: *mut sys::libinput_device,
+    // Taken while the context is known to be alive, its user data cannot be read once it is dropped
+    handler: Weak<Handler>,
 }
 
 /// Capabilities on a device. A device may have one or more capabilities at a time, capabilities remain static for the lifetime of the device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u32)]
 #[non_exhaustive]
 pub enum DeviceCapability {
@@ -27,18 +41,61 @@ pub enum DeviceCapability {
     Touch = sys::LIBINPUT_DEVICE_CAP_TOUCH,
 }
 
+impl DeviceCapability {
+    /// Every capability, in the order used by `libinput debug-events`
+    pub const ALL: [Self; 7] = [
+        Self::Keyboard,
+        Self::Pointer,
+        Self::Touch,
+        Self::TabletTool,
+        Self::TabletPad,
+        Self::Gesture,
+        Self::Switch,
+    ];
+
+    /// Returns the raw libinput capability
+    pub const fn as_raw(self) -> u32 {
+        self as u32
+    }
+
+    /// Returns the name of this capability as printed by `libinput debug-events`
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Gesture => "gesture",
+            Self::Keyboard => "keyboard",
+            Self::Pointer => "pointer",
+            Self::Switch => "switch",
+            Self::TabletPad => "tablet-pad",
+            Self::TabletTool => "tablet-tool",
+            Self::Touch => "touch",
+        }
+    }
+}
+
+impl std::fmt::Display for DeviceCapability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
 impl Device {
     /// Builds a new device from a raw libinput one
     ///
     /// # Safety
     ///
-    /// The caller must ensure it's passing a valid pointer
+    /// The caller must ensure it's passing a valid pointer to a device of a context created by colpetto
     pub unsafe fn from_raw(raw: *mut sys::libinput_device) -> Self {
         Self {
             raw: unsafe { sys::libinput_device_ref(raw) },
+            handler: unsafe { Handler::downgrade(sys::libinput_device_get_context(raw)) },
         }
     }
 
+    /// Returns the raw underlying pointer
+    pub fn as_raw(&self) -> *mut sys::libinput_device {
+        self.raw
+    }
+
     /// Returns the device group this device is assigned to.
     ///
     /// Some physical devices like graphics tablets are represented by multiple kernel
@@ -51,8 +108,9 @@ impl Device {
     ///
     /// All devices are part of a device group though for most devices the group will be
     /// a singleton. A device is assigned to a device group on [`DeviceAdded`](crate::event::DeviceAddedEvent)
-    /// and removed from that group on [`DeviceRemoved`](crate::event::DeviceRemovedEvent). It is up to the
-    /// caller to track how many devices are in each device group.
+    /// and removed from that group on [`DeviceRemoved`](crate::event::DeviceRemovedEvent). colpetto tracks
+    /// these events, use [`DeviceGroup::devices`] to list the members of a group and
+    /// [`DeviceGroup::apply_config`] to configure all of them at once.
     ///
     /// # Example Device Group Structure
     /// ```text
@@ -146,9 +204,152 @@ impl Device {
         Some(unsafe { devil::Device::from_raw(device.cast()) })
     }
 
+    /// Queries the udev device without leaking the reference like [`udev_device`](Self::udev_device) does
+    pub(crate) fn udev(&self) -> Option<UdevDevice> {
+        unsafe { UdevDevice::from_raw(sys::libinput_device_get_udev_device(self.raw).cast()) }
+    }
+
+    /// Returns an identity for this device that stays the same across replugs and context suspends,
+    /// unlike the device handle itself. See [`DeviceId`] for details.
+    pub fn device_id(&self) -> DeviceId {
+        DeviceId::new(self)
+    }
+
+    /// Returns the typed udev properties of this device, if the device has a udev device.
+    /// See [`DeviceProperties`] for details.
+    pub fn properties(&self) -> Option<DeviceProperties> {
+        let udev = self.udev()?;
+
+        let product = udev
+            .parent(c"input")
+            .and_then(|parent| Some(parent.property(c"PRODUCT")?.to_string_lossy().into_owned()));
+
+        let properties: Vec<_> = udev
+            .properties()
+            .map(|(key, value)| (key.to_string_lossy(), value.to_string_lossy()))
+            .collect();
+
+        Some(DeviceProperties::from_properties(
+            properties
+                .iter()
+                .map(|(key, value)| (key.as_ref(), value.as_ref()))
+                .chain(product.as_deref().map(|product| ("PRODUCT", product))),
+        ))
+    }
+
+    /// Returns the raw value of a udev property of this device, for properties not covered by [`properties`](Self::properties)
+    pub fn udev_property(&self, key: &CStr) -> Option<CString> {
+        Some(self.udev()?.property(key)?.to_owned())
+    }
+
     /// Check if the given device has the specified capability
     pub fn has_capability(&self, capability: DeviceCapability) -> bool {
-        unsafe { sys::libinput_device_has_capability(self.raw, capability as u32) != 0 }
+        unsafe { sys::libinput_device_has_capability(self.raw, capability.as_raw()) != 0 }
+    }
+
+    /// Returns every capability of the device as a set
+    pub fn capabilities(&self) -> Capabilities {
+        DeviceCapability::ALL
+            .into_iter()
+            .filter(|&capability| self.has_capability(capability))
+            .collect()
+    }
+
+    /// Returns a future that resolves once this device has been removed from its context.
+    ///
+    /// Removal is observed when the matching [`DeviceRemoved`](crate::event::DeviceRemovedEvent) event is read
+    /// through [`Libinput::get_event`](crate::Libinput::get_event), so the context must keep being polled,
+    /// e.g. through an [`EventStream`](crate::EventStream). The future resolves immediately if the device is not
+    /// known to the context, either because it was already removed or because its added event was never read.
+    ///
+    /// If the context was already dropped the future resolves immediately.
+    pub fn removed(&self) -> Removed {
+        let pending = self.handler.upgrade().map(|handler| PendingRemoval {
+            device: self.clone(),
+            tracker: Rc::downgrade(&handler.devices),
+            id: handler.devices.next_removal_id(),
+        });
+
+        Removed { pending }
+    }
+
+    /// Check if a device can be used in left-handed mode
+    pub fn config_left_handed_is_available(&self) -> bool {
+        unsafe { sys::libinput_device_config_left_handed_is_available(self.raw) != 0 }
+    }
+
+    /// Enable or disable left-handed mode. Changing the mode may not take effect immediately,
+    /// libinput waits until all buttons are released before switching.
+    pub fn config_left_handed_set(&self, enabled: bool) -> Result<(), ConfigError> {
+        ConfigError::from_status(unsafe {
+            sys::libinput_device_config_left_handed_set(self.raw, enabled.into())
+        })
+    }
+
+    /// Get the current left-handed mode of this device
+    pub fn config_left_handed(&self) -> bool {
+        unsafe { sys::libinput_device_config_left_handed_get(self.raw) != 0 }
+    }
+
+    /// Get the default left-handed mode of this device
+    pub fn config_left_handed_default(&self) -> bool {
+        unsafe { sys::libinput_device_config_left_handed_get_default(self.raw) != 0 }
+    }
+
+    /// Check whether a device can have a custom rotation applied
+    pub fn config_rotation_is_available(&self) -> bool {
+        unsafe { sys::libinput_device_config_rotation_is_available(self.raw) != 0 }
+    }
+
+    /// Set the rotation of a device in degrees clockwise off the logical neutral position.
+    /// Any subsequent motion events are adjusted according to the given angle.
+    pub fn config_rotation_set_angle(&self, degrees_cw: u32) -> Result<(), ConfigError> {
+        ConfigError::from_status(unsafe {
+            sys::libinput_device_config_rotation_set_angle(self.raw, degrees_cw)
+        })
+    }
+
+    /// Get the current rotation of a device in degrees clockwise
+    pub fn config_rotation_angle(&self) -> u32 {
+        unsafe { sys::libinput_device_config_rotation_get_angle(self.raw) }
+    }
+
+    /// Get the default rotation of a device in degrees clockwise
+    pub fn config_rotation_default_angle(&self) -> u32 {
+        unsafe { sys::libinput_device_config_rotation_get_default_angle(self.raw) }
+    }
+
+    /// Applies every option set in `config` to this device.
+    ///
+    /// Options the device does not support are skipped, the first invalid value aborts
+    /// and returns an error.
+    pub fn apply_config(&self, config: &DeviceConfig) -> Result<(), ConfigError> {
+        if let Some(enabled) = config.left_handed
+            && self.config_left_handed_is_available()
+        {
+            self.config_left_handed_set(enabled)?;
+        }
+
+        if let Some(degrees_cw) = config.rotation
+            && self.config_rotation_is_available()
+        {
+            self.config_rotation_set_angle(degrees_cw)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the current value of every option supported by this device, unsupported options are left as `None`.
+    /// Applying the result to a device with [`apply_config`](Self::apply_config) restores this state.
+    pub fn current_config(&self) -> DeviceConfig {
+        DeviceConfig {
+            left_handed: self
+                .config_left_handed_is_available()
+                .then(|| self.config_left_handed()),
+            rotation: self
+                .config_rotation_is_available()
+                .then(|| self.config_rotation_angle()),
+        }
     }
 }
 
@@ -162,8 +363,133 @@ impl Clone for Device {
     fn clone(&self) -> Self {
         Self {
             raw: unsafe { sys::libinput_device_ref(self.raw) },
+            handler: self.handler.clone(),
+        }
+    }
+}
+
+/// Future returned by [`Device::removed`].
+///
+/// Also resolves once the last handle to the context is dropped, as no device can be removed afterwards.
+#[must_use = "futures do nothing unless polled"]
+pub struct Removed {
+    /// `None` if the context was already dropped when the future was created
+    pending: Option<PendingRemoval>,
+}
+
+struct PendingRemoval {
+    device: Device,
+    // The context may be gone by the time this is polled, so its user data cannot be used
+    tracker: Weak<DeviceTracker>,
+    id: u64,
+}
+
+impl Future for Removed {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let Some(pending) = &self.pending else {
+            return Poll::Ready(());
+        };
+
+        let Some(tracker) = pending.tracker.upgrade() else {
+            return Poll::Ready(());
+        };
+
+        if !tracker.is_live(&pending.device) {
+            return Poll::Ready(());
+        }
+
+        tracker.register_removal(&pending.device, pending.id, cx.waker());
+
+        Poll::Pending
+    }
+}
+
+impl Drop for PendingRemoval {
+    fn drop(&mut self) {
+        if let Some(tracker) = self.tracker.upgrade() {
+            tracker.unregister_removal(&self.device, self.id);
+        }
+    }
+}
+
+impl std::fmt::Debug for Removed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Removed")
+            .field(
+                "device",
+                &self.pending.as_ref().map(|pending| &pending.device),
+            )
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for Device {
+    fn eq(&self, other: &Self) -> bool {
+        self.raw == other.raw
+    }
+}
+
+impl Eq for Device {}
+
+impl Hash for Device {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.raw.hash(state);
+    }
+}
+
+impl std::fmt::Debug for Device {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let alternate = f.alternate();
+
+        let mut debug = f.debug_struct("Device");
+
+        if alternate {
+            debug.field("raw", &self.raw);
         }
+
+        debug
+            .field("sysname", &self.sysname())
+            .field("name", &self.name())
+            .field("vendor", &format_args!("{:#06x}", self.vendor_id()))
+            .field("product", &format_args!("{:#06x}", self.product_id()))
+            .field("capabilities", &self.capabilities())
+            .finish()
+    }
+}
+
+/// Formats as `sysname: name`, e.g. `event5: AT Translated Set 2 keyboard`
+impl std::fmt::Display for Device {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {}",
+            self.sysname().to_string_lossy(),
+            self.name().to_string_lossy()
+        )
     }
 }
 
-macros::impl_debug!(Device, DeviceCapability);
+#[cfg(test)]
+mod tests {
+    use std::{mem::ManuallyDrop, ptr, task::Waker};
+
+    use super::*;
+
+    #[test]
+    fn removed_resolves_once_the_context_is_dropped() {
+        // A dead handle stands for a dropped context. The device is never used nor dropped,
+        // so no call is made into libinput.
+        let device = ManuallyDrop::new(Device {
+            raw: ptr::null_mut(),
+            handler: Weak::new(),
+        });
+
+        let mut removed = device.removed();
+        let mut cx = Context::from_waker(Waker::noop());
+
+        assert!(Pin::new(&mut removed).poll(&mut cx).is_ready());
+        assert!(Pin::new(&mut removed).poll(&mut cx).is_ready());
+    }
+}
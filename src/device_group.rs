@@ -1,8 +1,11 @@
-use crate::{macros, sys};
+use std::hash::{Hash, Hasher};
+
+use crate::{ConfigError, Device, DeviceConfig, sys, tracker::DeviceTracker};
 
 /// A base handle for accessing libinput device groups.
+///
+/// Two handles compare equal if they refer to the same libinput device group.
 pub struct DeviceGroup {
-    #[allow(unused)]
     raw: *mut sys::libinput_device_group,
 }
 
@@ -18,6 +21,41 @@ impl DeviceGroup {
             }
         }
     }
+
+    /// Returns the raw underlying pointer
+    pub fn as_raw(&self) -> *mut sys::libinput_device_group {
+        self.raw
+    }
+
+    /// Returns the devices currently assigned to this group.
+    ///
+    /// Membership is tracked from the [`DeviceAdded`](crate::event::DeviceAddedEvent) and
+    /// [`DeviceRemoved`](crate::event::DeviceRemovedEvent) events retrieved through
+    /// [`Libinput::get_event`](crate::Libinput::get_event), so a device shows up here only once its
+    /// added event has been read. The list is empty once the owning context is dropped.
+    pub fn devices(&self) -> Vec<Device> {
+        let tracker = unsafe { sys::libinput_device_group_get_user_data(self.raw) };
+
+        if tracker.is_null() {
+            return Vec::new();
+        }
+
+        // Safety: the pointer is set by the tracker and cleared before it goes away
+        let tracker = unsafe { &*(tracker as *const DeviceTracker) };
+
+        tracker.group_members(self)
+    }
+
+    /// Applies `config` to every device in this group.
+    ///
+    /// This is the typical way to handle devices that are physically linked, e.g. setting a
+    /// tablet to left-handed also needs the touch device on the same tablet to be turned upside down.
+    /// Devices that don't support an option are skipped, see [`Device::apply_config`].
+    pub fn apply_config(&self, config: &DeviceConfig) -> Result<(), ConfigError> {
+        self.devices()
+            .iter()
+            .try_for_each(|device| device.apply_config(config))
+    }
 }
 
 impl Drop for DeviceGroup {
@@ -34,4 +72,33 @@ impl Clone for DeviceGroup {
     }
 }
 
-macros::impl_debug!(DeviceGroup);
+impl PartialEq for DeviceGroup {
+    fn eq(&self, other: &Self) -> bool {
+        self.raw == other.raw
+    }
+}
+
+impl Eq for DeviceGroup {}
+
+impl Hash for DeviceGroup {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.raw.hash(state);
+    }
+}
+
+impl std::fmt::Debug for DeviceGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let devices = self.devices();
+        let sysnames: Vec<_> = devices.iter().map(Device::sysname).collect();
+
+        let alternate = f.alternate();
+
+        let mut debug = f.debug_struct("DeviceGroup");
+
+        if alternate {
+            debug.field("raw", &self.raw);
+        }
+
+        debug.field("devices", &sysnames).finish()
+    }
+}
@@ -0,0 +1,244 @@
+use std::{fmt, str::FromStr};
+
+use crate::{Device, InputId};
+
+/// A stable identity for a physical input device.
+///
+/// Unlike [`Device`] handles, which are recreated every time a device is plugged in or a context is resumed,
+/// a `DeviceId` is built only from properties of the hardware and of where it is connected:
+/// bus type, vendor and product ids, the device name, and the udev `ID_PATH` and `ID_SERIAL` properties.
+/// Two identical devices plugged into different ports have different ids, while the same device replugged
+/// into the same port gets the same id back. This makes it suitable as a key for per-device settings or
+/// statistics that need to be persisted.
+///
+/// The id can be turned into a string with [`Display`](fmt::Display) and parsed back with [`FromStr`].
+/// The string format is stable:
+///
+/// ```text
+/// 0003:046d:c52b/Logitech USB Receiver/pci-0000:00:14.0-usb-0:2:1.0/Logitech_USB_Receiver
+/// ```
+///
+/// Missing udev properties are left empty, and `%` and `/` inside the textual fields are percent encoded.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DeviceId {
+    /// The bus type of the device, as in `linux/input.h`
+    pub bustype: u32,
+    /// The vendor id of the device
+    pub vendor: u32,
+    /// The product id of the device
+    pub product: u32,
+    /// The descriptive device name, see [`Device::name`]
+    pub name: String,
+    /// The udev `ID_PATH` property, describing where the device is connected
+    pub id_path: Option<String>,
+    /// The udev `ID_SERIAL` property, usually only available on usb devices
+    pub id_serial: Option<String>,
+}
+
+impl DeviceId {
+    /// Builds the identity of a device. Prefer [`Device::device_id`].
+    pub fn new(device: &Device) -> Self {
+        let udev = device.udev();
+
+        let property = |key| {
+            udev.as_ref()
+                .and_then(|udev| udev.property(key))
+                .map(|value| value.to_string_lossy().into_owned())
+                .filter(|value| !value.is_empty())
+        };
+
+        let bustype = udev
+            .as_ref()
+            .and_then(|udev| udev.parent(c"input"))
+            .and_then(|parent| InputId::from_product(parent.property(c"PRODUCT")?.to_str().ok()?))
+            .map(|id| id.bustype)
+            .unwrap_or_default();
+
+        Self {
+            bustype,
+            vendor: device.vendor_id(),
+            product: device.product_id(),
+            name: device.name().to_string_lossy().into_owned(),
+            id_path: property(c"ID_PATH"),
+            id_serial: property(c"ID_SERIAL"),
+        }
+    }
+}
+
+impl fmt::Display for DeviceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04x}:{:04x}:{:04x}/{}/{}/{}",
+            self.bustype,
+            self.vendor,
+            self.product,
+            Escaped(&self.name),
+            Escaped(self.id_path.as_deref().unwrap_or_default()),
+            Escaped(self.id_serial.as_deref().unwrap_or_default()),
+        )
+    }
+}
+
+/// Error returned when parsing a [`DeviceId`] from a string fails
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Invalid device id: {0:?}")]
+pub struct ParseDeviceIdError(String);
+
+impl FromStr for DeviceId {
+    type Err = ParseDeviceIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseDeviceIdError(s.to_owned());
+
+        let mut fields = s.split('/');
+        let mut next = || fields.next().ok_or_else(error);
+
+        let ids = next()?;
+        let name = unescape(next()?).ok_or_else(error)?;
+        let id_path = unescape(next()?).ok_or_else(error)?;
+        let id_serial = unescape(next()?).ok_or_else(error)?;
+
+        if fields.next().is_some() {
+            return Err(error());
+        }
+
+        let mut ids = ids.split(':').map(|id| u32::from_str_radix(id, 16));
+
+        let (Some(Ok(bustype)), Some(Ok(vendor)), Some(Ok(product)), None) =
+            (ids.next(), ids.next(), ids.next(), ids.next())
+        else {
+            return Err(error());
+        };
+
+        Ok(Self {
+            bustype,
+            vendor,
+            product,
+            name,
+            id_path: Some(id_path).filter(|path| !path.is_empty()),
+            id_serial: Some(id_serial).filter(|serial| !serial.is_empty()),
+        })
+    }
+}
+
+struct Escaped<'a>(&'a str);
+
+impl fmt::Display for Escaped<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '%' => f.write_str("%25")?,
+                '/' => f.write_str("%2F")?,
+                c => write!(f, "{c}")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn unescape(s: &str) -> Option<String> {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(index) = rest.find('%') {
+        out.push_str(&rest[..index]);
+
+        match rest.get(index + 1..index + 3)? {
+            "25" => out.push('%'),
+            "2F" | "2f" => out.push('/'),
+            _ => return None,
+        }
+
+        rest = &rest[index + 3..];
+    }
+
+    out.push_str(rest);
+
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn logitech() -> DeviceId {
+        DeviceId {
+            bustype: 0x03,
+            vendor: 0x046d,
+            product: 0xc52b,
+            name: "Logitech USB Receiver".to_owned(),
+            id_path: Some("pci-0000:00:14.0-usb-0:2:1.0".to_owned()),
+            id_serial: Some("Logitech_USB_Receiver".to_owned()),
+        }
+    }
+
+    #[test]
+    fn display_matches_documented_format() {
+        assert_eq!(
+            logitech().to_string(),
+            "0003:046d:c52b/Logitech USB Receiver/pci-0000:00:14.0-usb-0:2:1.0/Logitech_USB_Receiver"
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let id = logitech();
+        assert_eq!(id.to_string().parse(), Ok(id));
+    }
+
+    #[test]
+    fn round_trip_escapes_separators() {
+        let id = DeviceId {
+            name: "Weird/Name 100%".to_owned(),
+            id_path: Some("platform/i8042%2F".to_owned()),
+            id_serial: Some("/%/".to_owned()),
+            ..logitech()
+        };
+
+        let string = id.to_string();
+        assert_eq!(string.matches('/').count(), 3);
+        assert_eq!(string.parse(), Ok(id));
+    }
+
+    #[test]
+    fn round_trip_missing_properties() {
+        let id = DeviceId {
+            id_path: None,
+            id_serial: None,
+            ..logitech()
+        };
+
+        assert_eq!(id.to_string(), "0003:046d:c52b/Logitech USB Receiver//");
+        assert_eq!(id.to_string().parse(), Ok(id));
+    }
+
+    #[test]
+    fn empty_properties_parse_as_none() {
+        let id: DeviceId = "0011:0001:0001/AT Translated Set 2 keyboard//"
+            .parse()
+            .unwrap();
+
+        assert_eq!(id.bustype, 0x11);
+        assert_eq!(id.id_path, None);
+        assert_eq!(id.id_serial, None);
+    }
+
+    #[test]
+    fn rejects_invalid() {
+        for invalid in [
+            "",
+            "0003:046d:c52b",
+            "0003:046d:c52b/name/path",
+            "0003:046d:c52b/name/path/serial/extra",
+            "0003:046d/name/path/serial",
+            "0003:046d:c52b:0001/name/path/serial",
+            "0003:046d:zzzz/name/path/serial",
+            "0003:046d:c52b/bad%escape/path/serial",
+            "0003:046d:c52b/truncated%2/path/serial",
+        ] {
+            assert!(invalid.parse::<DeviceId>().is_err(), "{invalid:?}");
+        }
+    }
+}
@@ -0,0 +1,528 @@
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    fmt::{self, Write},
+    io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{Capabilities, Device, DeviceConfig, DeviceId, LogPriority, LogRecord, OFlags};
+
+/// The libinput version colpetto was built against, from the selected version feature
+#[cfg(feature = "1_22")]
+const LIBINPUT_VERSION: &str = "1.22";
+#[cfg(feature = "1_23")]
+const LIBINPUT_VERSION: &str = "1.23";
+#[cfg(feature = "1_24")]
+const LIBINPUT_VERSION: &str = "1.24";
+#[cfg(feature = "1_25")]
+const LIBINPUT_VERSION: &str = "1.25";
+#[cfg(feature = "1_26")]
+const LIBINPUT_VERSION: &str = "1.26";
+#[cfg(feature = "1_27")]
+const LIBINPUT_VERSION: &str = "1.27";
+#[cfg(feature = "1_28")]
+const LIBINPUT_VERSION: &str = "1.28";
+#[cfg(feature = "1_29")]
+const LIBINPUT_VERSION: &str = "1.29";
+#[cfg(feature = "1_30")]
+const LIBINPUT_VERSION: &str = "1.30";
+#[cfg(feature = "1_31")]
+const LIBINPUT_VERSION: &str = "1.31";
+
+/// How many log records a context keeps for its diagnostics
+const LOG_HISTORY_LEN: usize = 256;
+/// How many open failures a context keeps for its diagnostics
+const OPEN_FAILURE_HISTORY_LEN: usize = 32;
+
+/// udev properties worth attaching to a bug report, matched by prefix
+const RELEVANT_PROPERTIES: [&str; 13] = [
+    "DEVNAME",
+    "ID_BUS",
+    "ID_INPUT",
+    "ID_MODEL",
+    "ID_PATH",
+    "ID_SEAT",
+    "ID_SERIAL",
+    "ID_VENDOR",
+    "EVDEV_",
+    "KEYBOARD_",
+    "LIBINPUT_",
+    "MOUSE_",
+    "WL_",
+];
+
+/// Bounded history of what happened on a context, stored in its handler
+#[derive(Default)]
+pub(crate) struct History {
+    log: RefCell<VecDeque<LogRecord>>,
+    open_failures: RefCell<VecDeque<OpenFailure>>,
+}
+
+impl History {
+    pub(crate) fn record_log(&self, record: &LogRecord) {
+        push_bounded(&mut self.log.borrow_mut(), record.clone(), LOG_HISTORY_LEN);
+    }
+
+    pub(crate) fn record_open_failure(&self, path: &Path, flags: OFlags, error: io::Error) {
+        let failure = OpenFailure {
+            path: path.to_owned(),
+            flags,
+            error,
+            time: SystemTime::now(),
+        };
+
+        push_bounded(
+            &mut self.open_failures.borrow_mut(),
+            failure,
+            OPEN_FAILURE_HISTORY_LEN,
+        );
+    }
+
+    pub(crate) fn log(&self) -> Vec<LogRecord> {
+        self.log.borrow().iter().cloned().collect()
+    }
+
+    pub(crate) fn open_failures(&self) -> Vec<OpenFailure> {
+        self.open_failures.borrow().iter().cloned().collect()
+    }
+
+    pub(crate) fn clear_open_failures(&self) {
+        self.open_failures.borrow_mut().clear();
+    }
+}
+
+fn push_bounded<T>(queue: &mut VecDeque<T>, value: T, len: usize) {
+    if queue.len() == len {
+        queue.pop_front();
+    }
+
+    queue.push_back(value);
+}
+
+/// A device that libinput failed to open through the open callback, see [`Libinput::open_failures`](crate::Libinput::open_failures)
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct OpenFailure {
+    /// The path libinput tried to open
+    pub path: PathBuf,
+    /// The flags passed to the open callback
+    pub flags: OFlags,
+    /// The error returned by the open callback
+    pub error: io::Error,
+    /// When the failure happened
+    pub time: SystemTime,
+}
+
+impl Clone for OpenFailure {
+    fn clone(&self) -> Self {
+        Self {
+            path: self.path.clone(),
+            flags: self.flags,
+            error: match self.error.raw_os_error() {
+                Some(errno) => io::Error::from_raw_os_error(errno),
+                None => io::Error::new(self.error.kind(), self.error.to_string()),
+            },
+            time: self.time,
+        }
+    }
+}
+
+impl fmt::Display for OpenFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (flags {:#o}): {}",
+            self.path.display(),
+            self.flags.bits(),
+            self.error
+        )
+    }
+}
+
+/// A snapshot of a libinput context meant to be attached to bug reports.
+///
+/// Returned by [`Libinput::diagnostics`](crate::Libinput::diagnostics). The report is plain data,
+/// it can be printed as text with its `Display` implementation or as JSON with [`to_json`](Self::to_json).
+///
+/// # Example usage
+///
+/// ```
+/// let report = libinput.diagnostics();
+///
+/// std::fs::write("colpetto-report.json", report.to_json())?;
+/// ```
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct Diagnostics {
+    /// The libinput version selected at compile time, e.g. `1.27`
+    pub libinput_version: &'static str,
+    /// The id of the context, see [`Libinput::context_id`](crate::Libinput::context_id)
+    pub context_id: u64,
+    /// The log priority of the context at the time of the report
+    pub log_priority: LogPriority,
+    /// The devices currently known to the context, sorted by sysname
+    pub devices: Vec<DeviceDiagnostics>,
+    /// The last log records of the context, oldest first
+    pub log: Vec<LogRecord>,
+    /// The last devices that failed to open, oldest first
+    pub open_failures: Vec<OpenFailure>,
+}
+
+/// The state of a single device in a [`Diagnostics`] report
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct DeviceDiagnostics {
+    /// The system name of the device, e.g. `event5`
+    pub sysname: String,
+    /// The name of the device
+    pub name: String,
+    /// The stable id of the device
+    pub id: DeviceId,
+    /// The capabilities of the device
+    pub capabilities: Capabilities,
+    /// The physical and logical names of the seat of the device
+    pub seat: String,
+    /// The current configuration of the device
+    pub config: DeviceConfig,
+    /// The udev properties relevant to input handling, sorted by key
+    pub udev_properties: Vec<(String, String)>,
+}
+
+impl DeviceDiagnostics {
+    pub(crate) fn new(device: &Device) -> Self {
+        let mut udev_properties: Vec<_> = device
+            .udev()
+            .map(|udev| {
+                udev.properties()
+                    .map(|(key, value)| {
+                        (
+                            key.to_string_lossy().into_owned(),
+                            value.to_string_lossy().into_owned(),
+                        )
+                    })
+                    .filter(|(key, _)| {
+                        RELEVANT_PROPERTIES
+                            .iter()
+                            .any(|prefix| key.starts_with(prefix))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        udev_properties.sort_unstable();
+
+        Self {
+            sysname: device.sysname().to_string_lossy().into_owned(),
+            name: device.name().to_string_lossy().into_owned(),
+            id: device.device_id(),
+            capabilities: device.capabilities(),
+            seat: device.seat().to_string(),
+            config: device.current_config(),
+            udev_properties,
+        }
+    }
+}
+
+impl Diagnostics {
+    pub(crate) fn new(
+        context_id: u64,
+        log_priority: LogPriority,
+        devices: &[Device],
+        history: &History,
+    ) -> Self {
+        let mut devices: Vec<_> = devices.iter().map(DeviceDiagnostics::new).collect();
+        devices.sort_unstable_by(|a, b| a.sysname.cmp(&b.sysname));
+
+        Self {
+            libinput_version: LIBINPUT_VERSION,
+            context_id,
+            log_priority,
+            devices,
+            log: history.log(),
+            open_failures: history.open_failures(),
+        }
+    }
+
+    /// Serializes the report as a JSON object
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+
+        json.push_str("{\"libinput_version\":");
+        push_json_str(&mut json, self.libinput_version);
+        let _ = write!(json, ",\"context_id\":{}", self.context_id);
+        json.push_str(",\"log_priority\":");
+        push_json_str(&mut json, &self.log_priority.to_string());
+
+        json.push_str(",\"devices\":[");
+        for (i, device) in self.devices.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+
+            json.push_str("{\"sysname\":");
+            push_json_str(&mut json, &device.sysname);
+            json.push_str(",\"name\":");
+            push_json_str(&mut json, &device.name);
+            json.push_str(",\"id\":");
+            push_json_str(&mut json, &device.id.to_string());
+
+            json.push_str(",\"capabilities\":[");
+            for (i, capability) in device.capabilities.iter().enumerate() {
+                if i > 0 {
+                    json.push(',');
+                }
+                push_json_str(&mut json, capability.name());
+            }
+            json.push(']');
+
+            json.push_str(",\"seat\":");
+            push_json_str(&mut json, &device.seat);
+
+            json.push_str(",\"config\":{\"left_handed\":");
+            push_json_option(&mut json, device.config.left_handed);
+            json.push_str(",\"rotation\":");
+            push_json_option(&mut json, device.config.rotation);
+            json.push('}');
+
+            json.push_str(",\"udev_properties\":{");
+            for (i, (key, value)) in device.udev_properties.iter().enumerate() {
+                if i > 0 {
+                    json.push(',');
+                }
+                push_json_str(&mut json, key);
+                json.push(':');
+                push_json_str(&mut json, value);
+            }
+            json.push_str("}}");
+        }
+        json.push(']');
+
+        json.push_str(",\"log\":[");
+        for (i, record) in self.log.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+
+            json.push_str("{\"priority\":");
+            push_json_str(&mut json, &record.priority.to_string());
+            json.push_str(",\"category\":");
+            push_json_str(&mut json, record.category.name());
+            json.push_str(",\"device\":");
+            match &record.device_sysname {
+                Some(sysname) => push_json_str(&mut json, sysname),
+                None => json.push_str("null"),
+            }
+            json.push_str(",\"message\":");
+            push_json_str(&mut json, &record.message);
+            json.push('}');
+        }
+        json.push(']');
+
+        json.push_str(",\"open_failures\":[");
+        for (i, failure) in self.open_failures.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+
+            json.push_str("{\"path\":");
+            push_json_str(&mut json, &failure.path.to_string_lossy());
+            let _ = write!(json, ",\"flags\":{}", failure.flags.bits());
+            json.push_str(",\"errno\":");
+            push_json_option(&mut json, failure.error.raw_os_error());
+            json.push_str(",\"error\":");
+            push_json_str(&mut json, &failure.error.to_string());
+            json.push_str(",\"time\":");
+            push_json_option(&mut json, unix_secs(failure.time));
+            json.push('}');
+        }
+        json.push_str("]}");
+
+        json
+    }
+}
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "libinput version: {}", self.libinput_version)?;
+        writeln!(f, "context: {}", self.context_id)?;
+        writeln!(f, "log priority: {}", self.log_priority)?;
+
+        writeln!(f, "\ndevices ({}):", self.devices.len())?;
+        for device in &self.devices {
+            writeln!(f, "  {}: {}", device.sysname, device.name)?;
+            writeln!(f, "    id: {}", device.id)?;
+            writeln!(f, "    capabilities: {}", device.capabilities)?;
+            writeln!(f, "    seat: {}", device.seat)?;
+
+            if let Some(left_handed) = device.config.left_handed {
+                writeln!(f, "    left handed: {left_handed}")?;
+            }
+
+            if let Some(rotation) = device.config.rotation {
+                writeln!(f, "    rotation: {rotation}")?;
+            }
+
+            for (key, value) in &device.udev_properties {
+                writeln!(f, "    {key}={value}")?;
+            }
+        }
+
+        writeln!(f, "\nopen failures ({}):", self.open_failures.len())?;
+        for failure in &self.open_failures {
+            writeln!(f, "  {failure}")?;
+        }
+
+        writeln!(f, "\nlog ({} records):", self.log.len())?;
+        for record in &self.log {
+            write!(f, "  [{}]", record.priority)?;
+
+            if let Some(sysname) = &record.device_sysname {
+                write!(f, " {sysname}")?;
+            }
+
+            writeln!(f, " {}: {}", record.category, record.message)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn unix_secs(time: SystemTime) -> Option<u64> {
+    time.duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|duration| duration.as_secs())
+}
+
+fn push_json_option(json: &mut String, value: Option<impl fmt::Display>) {
+    match value {
+        Some(value) => {
+            let _ = write!(json, "{value}");
+        }
+        None => json.push_str("null"),
+    }
+}
+
+fn push_json_str(json: &mut String, value: &str) {
+    json.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+
+    json.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{DeviceCapability, LogCategory};
+
+    fn json_str(value: &str) -> String {
+        let mut json = String::new();
+        push_json_str(&mut json, value);
+        json
+    }
+
+    #[test]
+    fn json_escapes_quotes_and_backslashes() {
+        assert_eq!(json_str(""), r#""""#);
+        assert_eq!(json_str("plain text"), r#""plain text""#);
+        assert_eq!(json_str(r#"say "hi""#), r#""say \"hi\"""#);
+        assert_eq!(json_str(r"C:\path\"), r#""C:\\path\\""#);
+    }
+
+    #[test]
+    fn json_escapes_control_characters() {
+        assert_eq!(json_str("a\nb\rc\td"), r#""a\nb\rc\td""#);
+        assert_eq!(
+            json_str("\u{0}\u{1}\u{1b}\u{1f}"),
+            r#""\u0000\u0001\u001b\u001f""#
+        );
+        assert_eq!(json_str("\u{7f}"), r#""\u007f""#);
+        assert_eq!(json_str("touché ⌨"), r#""touché ⌨""#);
+    }
+
+    #[test]
+    fn json_report() {
+        let report = Diagnostics {
+            libinput_version: "1.27",
+            context_id: 3,
+            log_priority: LogPriority::Error,
+            devices: vec![DeviceDiagnostics {
+                sysname: "event5".to_owned(),
+                name: "Keyboard \"Pro\"".to_owned(),
+                id: DeviceId {
+                    bustype: 0x03,
+                    vendor: 0x046d,
+                    product: 0xc52b,
+                    name: "Keyboard \"Pro\"".to_owned(),
+                    id_path: None,
+                    id_serial: None,
+                },
+                capabilities: DeviceCapability::Keyboard | DeviceCapability::Pointer,
+                seat: "seat0/default".to_owned(),
+                config: DeviceConfig {
+                    left_handed: Some(true),
+                    rotation: None,
+                },
+                udev_properties: vec![("ID_BUS".to_owned(), "usb".to_owned())],
+            }],
+            log: vec![LogRecord {
+                priority: LogPriority::Error,
+                category: LogCategory::KernelBug,
+                device_sysname: Some("event5".to_owned()),
+                message: "line\nbreak".to_owned(),
+            }],
+            open_failures: vec![OpenFailure {
+                path: PathBuf::from("/dev/input/event7"),
+                flags: OFlags::RDWR,
+                error: io::Error::from_raw_os_error(13),
+                time: UNIX_EPOCH + Duration::from_secs(42),
+            }],
+        };
+
+        assert_eq!(
+            report.to_json(),
+            concat!(
+                r#"{"libinput_version":"1.27","context_id":3,"log_priority":"error","devices":["#,
+                r#"{"sysname":"event5","name":"Keyboard \"Pro\"","id":"0003:046d:c52b/Keyboard \"Pro\"//","#,
+                r#""capabilities":["keyboard","pointer"],"seat":"seat0/default","#,
+                r#""config":{"left_handed":true,"rotation":null},"udev_properties":{"ID_BUS":"usb"}}],"#,
+                r#""log":[{"priority":"error","category":"kernel-bug","device":"event5","message":"line\nbreak"}],"#,
+                r#""open_failures":[{"path":"/dev/input/event7","flags":2,"errno":13,"#,
+                r#""error":"Permission denied (os error 13)","time":42}]}"#,
+            )
+        );
+    }
+
+    #[test]
+    fn json_empty_report() {
+        let report = Diagnostics {
+            libinput_version: "1.22",
+            context_id: 0,
+            log_priority: LogPriority::Info,
+            devices: Vec::new(),
+            log: Vec::new(),
+            open_failures: Vec::new(),
+        };
+
+        assert_eq!(
+            report.to_json(),
+            r#"{"libinput_version":"1.22","context_id":0,"log_priority":"info","devices":[],"log":[],"open_failures":[]}"#
+        );
+    }
+}
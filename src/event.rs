@@ -2,9 +2,14 @@
 
 use crate::{Device, sys};
 
+mod fmt;
+
+pub(crate) use fmt::{DescribeEvent, EventFields};
+
 pub mod device;
 pub mod gesture;
 pub mod keyboard;
+pub mod owned;
 pub mod pointer;
 pub mod switch;
 pub mod table_pad;
@@ -14,6 +19,7 @@ pub mod touch;
 pub use device::*;
 pub use gesture::*;
 pub use keyboard::*;
+pub use owned::*;
 pub use pointer::*;
 pub use switch::*;
 pub use table_pad::*;
@@ -27,6 +33,7 @@ macro_rules! define_events {
         $raw:ident,
         $get:expr_2021,
         $set:expr_2021,
+        $time:expr_2021,
         $(
             $(#[$event_meta:meta])*
             $event:ident,
@@ -54,6 +61,16 @@ macro_rules! define_events {
                 }
             }
 
+            impl std::fmt::Display for [<$main Event>] {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    match self {
+                        $(
+                            Self::$event(e) => std::fmt::Display::fmt(e, f),
+                        )+
+                    }
+                }
+            }
+
             impl crate::event::sealed::EventSealed for [<$main Event>] {}
 
             impl crate::event::AsRawEvent for [<$main Event>] {
@@ -72,7 +89,35 @@ macro_rules! define_events {
                     raw: *mut $raw,
                 }
 
-                crate::macros::impl_debug!([<$main $event Event>]);
+                impl [<$main $event Event>] {
+                    fn event_time_usec(&self) -> Option<u64> {
+                        let time: Option<unsafe extern "C" fn(*mut $raw) -> u64> = $time;
+                        time.map(|time| unsafe { time(self.raw) })
+                    }
+                }
+
+                impl std::fmt::Debug for [<$main $event Event>] {
+                    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        crate::event::fmt::debug(
+                            self,
+                            stringify!([<$main $event Event>]),
+                            self.raw,
+                            self.event_time_usec(),
+                            f,
+                        )
+                    }
+                }
+
+                impl std::fmt::Display for [<$main $event Event>] {
+                    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        crate::event::fmt::display(
+                            self,
+                            stringify!([<$main:snake:upper _ $event:snake:upper>]),
+                            self.event_time_usec(),
+                            f,
+                        )
+                    }
+                }
 
                 impl crate::event::sealed::EventSealed for [<$main $event Event>] {}
 
@@ -103,7 +148,35 @@ macro_rules! define_events {
     };
 }
 
+/// Generates the `time` and `time_usec` accessors for events that carry a timestamp
+macro_rules! impl_time {
+    ($time:path, $time_usec:path, $($event:ident),+ $(,)?) => {
+        $(
+            impl $event {
+                /// The event time for this event
+                ///
+                /// # Note
+                ///
+                /// Timestamps may not always increase. See the libinput documentation for more details.
+                pub fn time(&self) -> u32 {
+                    unsafe { $time(self.raw) }
+                }
+
+                /// The event time for this event in microseconds
+                ///
+                /// # Note
+                ///
+                /// Timestamps may not always increase. See the libinput documentation for more details.
+                pub fn time_usec(&self) -> u64 {
+                    unsafe { $time_usec(self.raw) }
+                }
+            }
+        )+
+    };
+}
+
 pub(crate) use define_events;
+pub(crate) use impl_time;
 
 /// Rappresents a generic libinput event
 ///
@@ -139,6 +212,22 @@ impl Event {
     }
 }
 
+impl std::fmt::Display for Event {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Event::Device(e) => e.fmt(f),
+            Event::Gesture(e) => e.fmt(f),
+            Event::Keyboard(e) => e.fmt(f),
+            Event::Pointer(e) => e.fmt(f),
+            Event::Switch(e) => e.fmt(f),
+            Event::TabletPad(e) => e.fmt(f),
+            Event::TabletTool(e) => e.fmt(f),
+            Event::Touch(e) => e.fmt(f),
+            Event::Unknown(e) => e.fmt(f),
+        }
+    }
+}
+
 impl sealed::EventSealed for Event {}
 
 impl AsRawEvent for Event {
@@ -158,9 +247,7 @@ impl AsRawEvent for Event {
 }
 
 /// A special event that's not actually part of libinput but allows for graceful handling of newer versions
-#[derive(Debug)]
 pub struct Unknown {
-    #[allow(unused)]
     raw: *mut sys::libinput_event,
 }
 
@@ -171,6 +258,24 @@ impl Unknown {
     }
 }
 
+impl std::fmt::Debug for Unknown {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fmt::debug(self, "Unknown", self.raw, None, f)
+    }
+}
+
+impl std::fmt::Display for Unknown {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fmt::display(self, "UNKNOWN", None, f)
+    }
+}
+
+impl DescribeEvent for Unknown {
+    fn describe(&self, fields: &mut EventFields<'_, '_>) {
+        fields.field("type", &unsafe { sys::libinput_event_get_type(self.raw) });
+    }
+}
+
 impl sealed::EventSealed for Unknown {}
 
 impl AsRawEvent for Unknown {
@@ -1,8 +1,11 @@
 //! Device events are generated when a device is added/removed
 
-use crate::sys::{
-    libinput_event_device_notify, libinput_event_device_notify_get_base_event,
-    libinput_event_get_device_notify_event,
+use crate::{
+    event::{AsRawEvent, DescribeEvent, EventFields},
+    sys::{
+        libinput_event_device_notify, libinput_event_device_notify_get_base_event,
+        libinput_event_get_device_notify_event,
+    },
 };
 
 super::define_events!(
@@ -11,6 +14,7 @@ super::define_events!(
     libinput_event_device_notify,
     libinput_event_device_notify_get_base_event,
     libinput_event_get_device_notify_event,
+    None,
     /// Signals that a device has been added to the context.
     /// The device will not be read until the next time the user calls libinput_dispatch() and data is available.
     /// This allows setting up initial device configuration before any events are created.
@@ -18,3 +22,19 @@ super::define_events!(
     /// Signals that a device has been removed. No more events from the associated device will be in the queue or be queued after this event.
     Removed,
 );
+
+impl DescribeEvent for DeviceAddedEvent {
+    fn describe(&self, fields: &mut EventFields<'_, '_>) {
+        let device = self.device();
+
+        fields
+            .field("name", &device.name())
+            .field("seat", &format_args!("{}", device.seat()));
+    }
+}
+
+impl DescribeEvent for DeviceRemovedEvent {
+    fn describe(&self, fields: &mut EventFields<'_, '_>) {
+        fields.field("name", &self.device().name());
+    }
+}
@@ -0,0 +1,107 @@
+//! Shared formatting for events, loosely following the output of `libinput debug-events`
+
+use std::fmt;
+
+use super::AsRawEvent;
+
+/// Lists the interesting fields of an event, used by both its `Debug` and `Display` implementations
+pub(crate) trait DescribeEvent {
+    fn describe(&self, fields: &mut EventFields<'_, '_>) {
+        let _ = fields;
+    }
+}
+
+/// Collects the fields of an event either into a debug struct or into a single `key=value` line
+pub(crate) struct EventFields<'a, 'b> {
+    inner: Inner<'a, 'b>,
+}
+
+enum Inner<'a, 'b> {
+    Debug(fmt::DebugStruct<'a, 'b>),
+    Display(&'a mut fmt::Formatter<'b>, fmt::Result),
+}
+
+impl EventFields<'_, '_> {
+    pub(crate) fn field(&mut self, name: &str, value: &dyn fmt::Debug) -> &mut Self {
+        match &mut self.inner {
+            Inner::Debug(debug) => {
+                debug.field(name, value);
+            }
+            Inner::Display(f, result) => {
+                if result.is_ok() {
+                    *result = write!(f, " {name}={value:?}");
+                }
+            }
+        }
+
+        self
+    }
+
+    fn finish(self) -> fmt::Result {
+        match self.inner {
+            Inner::Debug(mut debug) => debug.finish(),
+            Inner::Display(_, result) => result,
+        }
+    }
+}
+
+pub(crate) fn debug<E>(
+    event: &E,
+    name: &str,
+    raw: *const impl Sized,
+    time_usec: Option<u64>,
+    f: &mut fmt::Formatter<'_>,
+) -> fmt::Result
+where
+    E: AsRawEvent + DescribeEvent,
+{
+    let alternate = f.alternate();
+    let mut debug = f.debug_struct(name);
+
+    if alternate {
+        debug.field("raw", &raw);
+    }
+
+    debug.field("device", &event.device().sysname());
+
+    if let Some(time_usec) = time_usec {
+        debug.field("time_usec", &time_usec);
+    }
+
+    let mut fields = EventFields {
+        inner: Inner::Debug(debug),
+    };
+
+    event.describe(&mut fields);
+
+    fields.finish()
+}
+
+pub(crate) fn display<E>(
+    event: &E,
+    event_type: &str,
+    time_usec: Option<u64>,
+    f: &mut fmt::Formatter<'_>,
+) -> fmt::Result
+where
+    E: AsRawEvent + DescribeEvent,
+{
+    write!(
+        f,
+        "{:<8} {:<26}",
+        event.device().sysname().to_string_lossy(),
+        event_type
+    )?;
+
+    if let Some(time_usec) = time_usec {
+        write!(f, " +{:.3}s", time_usec as f64 / 1_000_000.0)?;
+    }
+
+    let mut fields = EventFields {
+        inner: Inner::Display(f, Ok(())),
+    };
+
+    event.describe(&mut fields);
+
+    fields.finish()
+}
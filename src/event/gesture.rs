@@ -2,8 +2,12 @@
 
 //! Gesture events are generated when a gesture is recognized on a touchpad.
 
-use crate::sys::{
-    libinput_event_gesture, libinput_event_gesture_get_base_event, libinput_event_get_gesture_event,
+use crate::{
+    event::{DescribeEvent, EventFields, impl_time},
+    sys::{
+        self, libinput_event_gesture, libinput_event_gesture_get_base_event,
+        libinput_event_get_gesture_event,
+    },
 };
 
 super::define_events!(
@@ -11,6 +15,7 @@ super::define_events!(
     libinput_event_gesture,
     libinput_event_gesture_get_base_event,
     libinput_event_get_gesture_event,
+    Some(sys::libinput_event_gesture_get_time_usec),
     SwipeBegin,
     SwipeUpdate,
     SwipeEnd,
@@ -20,3 +25,165 @@ super::define_events!(
     HoldBegin,
     HoldEnd,
 );
+
+impl_time!(
+    sys::libinput_event_gesture_get_time,
+    sys::libinput_event_gesture_get_time_usec,
+    GestureSwipeBeginEvent,
+    GestureSwipeUpdateEvent,
+    GestureSwipeEndEvent,
+    GesturePinchBeginEvent,
+    GesturePinchUpdateEvent,
+    GesturePinchEndEvent,
+    GestureHoldBeginEvent,
+    GestureHoldEndEvent,
+);
+
+/// Generates the finger count accessor shared by every gesture event
+macro_rules! impl_finger_count {
+    ($($event:ident),+ $(,)?) => {
+        $(
+            impl $event {
+                /// The number of fingers used for a gesture.
+                pub fn finger_count(&self) -> i32 {
+                    unsafe { sys::libinput_event_gesture_get_finger_count(self.raw) }
+                }
+            }
+        )+
+    };
+}
+
+impl_finger_count!(
+    GestureSwipeBeginEvent,
+    GestureSwipeUpdateEvent,
+    GestureSwipeEndEvent,
+    GesturePinchBeginEvent,
+    GesturePinchUpdateEvent,
+    GesturePinchEndEvent,
+    GestureHoldBeginEvent,
+    GestureHoldEndEvent,
+);
+
+/// Generates the motion accessors shared by the update events
+macro_rules! impl_delta {
+    ($($event:ident),+ $(,)?) => {
+        $(
+            impl $event {
+                /// The delta between the last event and the current event on the x axis, with acceleration applied.
+                pub fn dx(&self) -> f64 {
+                    unsafe { sys::libinput_event_gesture_get_dx(self.raw) }
+                }
+
+                /// The delta between the last event and the current event on the y axis, with acceleration applied.
+                pub fn dy(&self) -> f64 {
+                    unsafe { sys::libinput_event_gesture_get_dy(self.raw) }
+                }
+
+                /// The relative delta of the unaccelerated motion vector on the x axis.
+                pub(crate) fn dx_unaccelerated(&self) -> f64 {
+                    unsafe { sys::libinput_event_gesture_get_dx_unaccelerated(self.raw) }
+                }
+
+                /// The relative delta of the unaccelerated motion vector on the y axis.
+                pub(crate) fn dy_unaccelerated(&self) -> f64 {
+                    unsafe { sys::libinput_event_gesture_get_dy_unaccelerated(self.raw) }
+                }
+            }
+        )+
+    };
+}
+
+impl_delta!(GestureSwipeUpdateEvent, GesturePinchUpdateEvent);
+
+/// Generates the accessors shared by every pinch event
+macro_rules! impl_pinch {
+    ($($event:ident),+ $(,)?) => {
+        $(
+            impl $event {
+                /// The absolute scale of a pinch gesture, relative to the begin event which always has a scale of 1.0.
+                pub fn scale(&self) -> f64 {
+                    unsafe { sys::libinput_event_gesture_get_scale(self.raw) }
+                }
+
+                /// The angle delta in degrees between the last and the current update event, clockwise positive.
+                pub fn angle_delta(&self) -> f64 {
+                    unsafe { sys::libinput_event_gesture_get_angle_delta(self.raw) }
+                }
+            }
+        )+
+    };
+}
+
+impl_pinch!(
+    GesturePinchBeginEvent,
+    GesturePinchUpdateEvent,
+    GesturePinchEndEvent,
+);
+
+/// Generates the cancelled accessor shared by the end events
+macro_rules! impl_cancelled {
+    ($($event:ident),+ $(,)?) => {
+        $(
+            impl $event {
+                /// Whether the gesture ended normally, or if it was cancelled.
+                pub fn cancelled(&self) -> bool {
+                    unsafe { sys::libinput_event_gesture_get_cancelled(self.raw) != 0 }
+                }
+            }
+
+            impl DescribeEvent for $event {
+                fn describe(&self, fields: &mut EventFields<'_, '_>) {
+                    fields
+                        .field("fingers", &self.finger_count())
+                        .field("cancelled", &self.cancelled());
+                }
+            }
+        )+
+    };
+}
+
+impl_cancelled!(
+    GestureSwipeEndEvent,
+    GesturePinchEndEvent,
+    GestureHoldEndEvent,
+);
+
+impl DescribeEvent for GestureSwipeBeginEvent {
+    fn describe(&self, fields: &mut EventFields<'_, '_>) {
+        fields.field("fingers", &self.finger_count());
+    }
+}
+
+impl DescribeEvent for GestureHoldBeginEvent {
+    fn describe(&self, fields: &mut EventFields<'_, '_>) {
+        fields.field("fingers", &self.finger_count());
+    }
+}
+
+impl DescribeEvent for GesturePinchBeginEvent {
+    fn describe(&self, fields: &mut EventFields<'_, '_>) {
+        fields
+            .field("fingers", &self.finger_count())
+            .field("scale", &self.scale());
+    }
+}
+
+impl DescribeEvent for GestureSwipeUpdateEvent {
+    fn describe(&self, fields: &mut EventFields<'_, '_>) {
+        fields
+            .field("fingers", &self.finger_count())
+            .field("dx", &self.dx())
+            .field("dy", &self.dy());
+    }
+}
+
+impl DescribeEvent for GesturePinchUpdateEvent {
+    fn describe(&self, fields: &mut EventFields<'_, '_>) {
+        fields
+            .field("fingers", &self.finger_count())
+            .field("dx", &self.dx())
+            .field("dy", &self.dy())
+            .field("scale", &self.scale())
+            .field("angle_delta", &self.angle_delta());
+    }
+}
@@ -1,8 +1,11 @@
 //! Key events are generated when a key changes its logical state, usually by being pressed or released.
 
-use crate::sys::{
-    self, libinput_event_get_keyboard_event, libinput_event_keyboard,
-    libinput_event_keyboard_get_base_event,
+use crate::{
+    event::{DescribeEvent, EventFields},
+    sys::{
+        self, libinput_event_get_keyboard_event, libinput_event_keyboard,
+        libinput_event_keyboard_get_base_event,
+    },
 };
 
 super::define_events!(
@@ -11,6 +14,7 @@ super::define_events!(
     libinput_event_keyboard,
     libinput_event_keyboard_get_base_event,
     libinput_event_get_keyboard_event,
+    Some(sys::libinput_event_keyboard_get_time_usec),
     /// Signals that a device has been removed. No more events from the associated device will be in the queue or be queued after this event.
     Key,
 );
@@ -50,8 +54,18 @@ impl KeyboardKeyEvent {
     }
 }
 
+impl DescribeEvent for KeyboardKeyEvent {
+    fn describe(&self, fields: &mut EventFields<'_, '_>) {
+        fields
+            .field("key", &self.key())
+            .field("state", &self.key_state())
+            .field("seat_key_count", &self.seat_key_count());
+    }
+}
+
 /// Logical state of a key. Note that the logical state may not represent the physical state of the key.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum KeyState {
     /// Logical released state
     Released,
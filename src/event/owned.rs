@@ -0,0 +1,1272 @@
+//! Plain-data copies of events, which can be sent across threads.
+//!
+//! Events borrow their data from the libinput context they were read from, so they cannot outlive it nor leave its thread.
+//! [`Event::to_owned`] reads every field of an event once and returns an [`OwnedEvent`], which mirrors the [`Event`] hierarchy
+//! with the same variants, a [`DeviceInfo`] snapshot of the device it comes from and its timestamp.
+//! The snapshot is taken once per device and shared by all of its events.
+//!
+//! # Example usage
+//!
+//! ```
+//! use colpetto::event::{OwnedEvent, OwnedPointerEvent};
+//!
+//! let owned = event.to_owned();
+//!
+//! std::thread::spawn(move || {
+//!     if let OwnedEvent::Pointer(OwnedPointerEvent::Motion(motion)) = owned {
+//!         println!("{} moved by {}x{}", motion.device.name, motion.dx, motion.dy);
+//!     }
+//! });
+//! ```
+
+use std::{ffi::CStr, sync::Arc};
+
+use crate::{
+    Capabilities, Device, DeviceId, Handler,
+    event::{
+        AsRawEvent, AxisSource, ButtonState, DeviceEvent, Event, GestureEvent, KeyState,
+        KeyboardEvent, PadAxisSource, PointerAxis, PointerEvent, ProximityState, Switch,
+        SwitchEvent, SwitchState, TabletPadEvent, TabletToolEvent, TabletToolType, TipState,
+        TouchEvent, Unknown,
+    },
+    sys,
+};
+
+/// A snapshot of the device an event comes from.
+///
+/// Taken when the first event of the device is converted, later events of the same device share it.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct DeviceInfo {
+    /// The system name of the device, e.g. `event5`
+    pub sysname: String,
+    /// The descriptive device name, see [`Device::name`]
+    pub name: String,
+    /// The vendor id of the device
+    pub vendor_id: u32,
+    /// The product id of the device
+    pub product_id: u32,
+    /// The output the device is mapped to, see [`Device::output_name`]
+    pub output_name: Option<String>,
+    /// The physical name of the seat of the device
+    pub physical_seat: String,
+    /// The logical name of the seat of the device
+    pub logical_seat: String,
+    /// The capabilities of the device
+    pub capabilities: Capabilities,
+    /// The physical size of the device in mm as width and height, for absolute devices.
+    /// Absolute coordinates in mm can be mapped to a screen of a given width by scaling with it.
+    pub size: Option<(f64, f64)>,
+    /// The stable identity of the device, see [`DeviceId`]
+    pub id: DeviceId,
+}
+
+impl DeviceInfo {
+    /// Takes a snapshot of a device
+    pub fn new(device: &Device) -> Self {
+        let seat = device.seat();
+
+        let (mut width, mut height) = (0.0, 0.0);
+        let size =
+            (unsafe { sys::libinput_device_get_size(device.as_raw(), &mut width, &mut height) }
+                == 0)
+                .then_some((width, height));
+
+        Self {
+            sysname: string(device.sysname()),
+            name: string(device.name()),
+            vendor_id: device.vendor_id(),
+            product_id: device.product_id(),
+            output_name: device.output_name().map(string),
+            physical_seat: string(seat.physical_name()),
+            logical_seat: string(seat.logical_name()),
+            capabilities: device.capabilities(),
+            size,
+            id: device.device_id(),
+        }
+    }
+}
+
+fn string(value: &CStr) -> String {
+    value.to_string_lossy().into_owned()
+}
+
+/// Generates an owned event category, with the accessors shared by all of its events
+macro_rules! owned_events {
+    (
+        $(#[$main_meta:meta])*
+        $main:ident,
+        $(
+            $(#[$event_meta:meta])*
+            $event:ident,
+        )+
+    ) => {
+        paste::paste! {
+            $(#[$main_meta])*
+            #[derive(Debug, Clone, PartialEq)]
+            #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+            #[non_exhaustive]
+            pub enum [<Owned $main Event>] {
+                $(
+                    $(#[$event_meta])*
+                    $event([<Owned $main $event Event>]),
+                )+
+            }
+
+            impl [<Owned $main Event>] {
+                /// Returns a printable string rappresenting the event type, the same as the borrowed event's
+                pub const fn event_type(&self) -> &'static str {
+                    match self {
+                        $(
+                            Self::$event(_) => stringify!($main $event),
+                        )+
+                    }
+                }
+
+                /// The device the event comes from
+                pub fn device(&self) -> &DeviceInfo {
+                    match self {
+                        $(
+                            Self::$event(e) => &e.device,
+                        )+
+                    }
+                }
+            }
+        }
+    };
+}
+
+/// Generates the `time_usec` accessor of owned event categories whose events all carry a timestamp
+macro_rules! owned_time {
+    ($main:ident, $($event:ident),+ $(,)?) => {
+        impl $main {
+            /// The event time for this event in microseconds
+            pub const fn time_usec(&self) -> u64 {
+                match self {
+                    $(
+                        Self::$event(e) => e.time_usec,
+                    )+
+                }
+            }
+        }
+    };
+}
+
+/// An owned copy of an [`Event`], see the [module documentation](self)
+#[allow(missing_docs)] // Mirrors `Event`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OwnedEvent {
+    Device(OwnedDeviceEvent),
+    Gesture(OwnedGestureEvent),
+    Keyboard(OwnedKeyboardEvent),
+    Pointer(OwnedPointerEvent),
+    Switch(OwnedSwitchEvent),
+    TabletPad(OwnedTabletPadEvent),
+    TabletTool(OwnedTabletToolEvent),
+    Touch(OwnedTouchEvent),
+    Unknown(OwnedUnknown),
+}
+
+impl OwnedEvent {
+    /// Returns a printable string rappresenting the event type, the same as [`Event::event_type`]
+    pub const fn event_type(&self) -> &'static str {
+        match self {
+            OwnedEvent::Device(e) => e.event_type(),
+            OwnedEvent::Gesture(e) => e.event_type(),
+            OwnedEvent::Keyboard(e) => e.event_type(),
+            OwnedEvent::Pointer(e) => e.event_type(),
+            OwnedEvent::Switch(e) => e.event_type(),
+            OwnedEvent::TabletPad(e) => e.event_type(),
+            OwnedEvent::TabletTool(e) => e.event_type(),
+            OwnedEvent::Touch(e) => e.event_type(),
+            OwnedEvent::Unknown(_) => "unknown",
+        }
+    }
+
+    /// The device the event comes from
+    pub fn device(&self) -> &DeviceInfo {
+        match self {
+            OwnedEvent::Device(e) => e.device(),
+            OwnedEvent::Gesture(e) => e.device(),
+            OwnedEvent::Keyboard(e) => e.device(),
+            OwnedEvent::Pointer(e) => e.device(),
+            OwnedEvent::Switch(e) => e.device(),
+            OwnedEvent::TabletPad(e) => e.device(),
+            OwnedEvent::TabletTool(e) => e.device(),
+            OwnedEvent::Touch(e) => e.device(),
+            OwnedEvent::Unknown(e) => &e.device,
+        }
+    }
+
+    /// The event time in microseconds, device and unknown events have none
+    pub const fn time_usec(&self) -> Option<u64> {
+        match self {
+            OwnedEvent::Device(_) | OwnedEvent::Unknown(_) => None,
+            OwnedEvent::Gesture(e) => Some(e.time_usec()),
+            OwnedEvent::Keyboard(e) => Some(e.time_usec()),
+            OwnedEvent::Pointer(e) => Some(e.time_usec()),
+            OwnedEvent::Switch(e) => Some(e.time_usec()),
+            OwnedEvent::TabletPad(e) => Some(e.time_usec()),
+            OwnedEvent::TabletTool(e) => Some(e.time_usec()),
+            OwnedEvent::Touch(e) => Some(e.time_usec()),
+        }
+    }
+}
+
+impl Event {
+    /// Copies every field of the event into an [`OwnedEvent`], which can outlive the context and be sent across threads.
+    ///
+    /// The [`DeviceInfo`] of a device is built once and shared, every other field is read again on each call.
+    pub fn to_owned(&self) -> OwnedEvent {
+        let device = self.device();
+        // Safe because the context outlives its events
+        let handler =
+            unsafe { Handler::from_context(sys::libinput_device_get_context(device.as_raw())) };
+        let device = handler.devices.device_info(&device);
+
+        match self {
+            Event::Device(e) => OwnedEvent::Device(device_event(e, device)),
+            Event::Gesture(e) => OwnedEvent::Gesture(gesture_event(e, device)),
+            Event::Keyboard(e) => OwnedEvent::Keyboard(keyboard_event(e, device)),
+            Event::Pointer(e) => OwnedEvent::Pointer(pointer_event(e, device)),
+            Event::Switch(e) => OwnedEvent::Switch(switch_event(e, device)),
+            Event::TabletPad(e) => OwnedEvent::TabletPad(tablet_pad_event(e, device)),
+            Event::TabletTool(e) => OwnedEvent::TabletTool(tablet_tool_event(e, device)),
+            Event::Touch(e) => OwnedEvent::Touch(touch_event(e, device)),
+            Event::Unknown(e) => OwnedEvent::Unknown(unknown_event(e, device)),
+        }
+    }
+}
+
+owned_events!(
+    /// An owned copy of a [`DeviceEvent`]
+    Device,
+    /// See [`DeviceAddedEvent`](crate::event::DeviceAddedEvent)
+    Added,
+    /// See [`DeviceRemovedEvent`](crate::event::DeviceRemovedEvent)
+    Removed,
+);
+
+/// An owned copy of a [`DeviceAddedEvent`](crate::event::DeviceAddedEvent)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct OwnedDeviceAddedEvent {
+    /// The device that was added
+    pub device: Arc<DeviceInfo>,
+}
+
+/// An owned copy of a [`DeviceRemovedEvent`](crate::event::DeviceRemovedEvent)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct OwnedDeviceRemovedEvent {
+    /// The device that was removed
+    pub device: Arc<DeviceInfo>,
+}
+
+fn device_event(event: &DeviceEvent, device: Arc<DeviceInfo>) -> OwnedDeviceEvent {
+    match event {
+        DeviceEvent::Added(_) => OwnedDeviceEvent::Added(OwnedDeviceAddedEvent { device }),
+        DeviceEvent::Removed(_) => OwnedDeviceEvent::Removed(OwnedDeviceRemovedEvent { device }),
+    }
+}
+
+owned_events!(
+    /// An owned copy of a [`GestureEvent`]
+    Gesture,
+    /// See [`GestureSwipeBeginEvent`](crate::event::GestureSwipeBeginEvent)
+    SwipeBegin,
+    /// See [`GestureSwipeUpdateEvent`](crate::event::GestureSwipeUpdateEvent)
+    SwipeUpdate,
+    /// See [`GestureSwipeEndEvent`](crate::event::GestureSwipeEndEvent)
+    SwipeEnd,
+    /// See [`GesturePinchBeginEvent`](crate::event::GesturePinchBeginEvent)
+    PinchBegin,
+    /// See [`GesturePinchUpdateEvent`](crate::event::GesturePinchUpdateEvent)
+    PinchUpdate,
+    /// See [`GesturePinchEndEvent`](crate::event::GesturePinchEndEvent)
+    PinchEnd,
+    /// See [`GestureHoldBeginEvent`](crate::event::GestureHoldBeginEvent)
+    HoldBegin,
+    /// See [`GestureHoldEndEvent`](crate::event::GestureHoldEndEvent)
+    HoldEnd,
+);
+
+owned_time!(
+    OwnedGestureEvent,
+    SwipeBegin,
+    SwipeUpdate,
+    SwipeEnd,
+    PinchBegin,
+    PinchUpdate,
+    PinchEnd,
+    HoldBegin,
+    HoldEnd,
+);
+
+/// An owned copy of the begin events of a gesture
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct OwnedGestureBegin {
+    /// The device the event comes from
+    pub device: Arc<DeviceInfo>,
+    /// The event time in microseconds
+    pub time_usec: u64,
+    /// The number of fingers used for the gesture
+    pub finger_count: i32,
+}
+
+/// An owned copy of the update events of a swipe gesture
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct OwnedGestureSwipeUpdateEvent {
+    /// The device the event comes from
+    pub device: Arc<DeviceInfo>,
+    /// The event time in microseconds
+    pub time_usec: u64,
+    /// The number of fingers used for the gesture
+    pub finger_count: i32,
+    /// The delta on the x axis, with acceleration applied
+    pub dx: f64,
+    /// The delta on the y axis, with acceleration applied
+    pub dy: f64,
+    /// The unaccelerated delta on the x axis
+    pub dx_unaccelerated: f64,
+    /// The unaccelerated delta on the y axis
+    pub dy_unaccelerated: f64,
+}
+
+/// An owned copy of the begin event of a pinch gesture
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct OwnedGesturePinchBeginEvent {
+    /// The device the event comes from
+    pub device: Arc<DeviceInfo>,
+    /// The event time in microseconds
+    pub time_usec: u64,
+    /// The number of fingers used for the gesture
+    pub finger_count: i32,
+    /// The absolute scale of the gesture, always 1.0 for begin events
+    pub scale: f64,
+    /// The angle delta in degrees, clockwise positive
+    pub angle_delta: f64,
+}
+
+/// An owned copy of the update events of a pinch gesture
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct OwnedGesturePinchUpdateEvent {
+    /// The device the event comes from
+    pub device: Arc<DeviceInfo>,
+    /// The event time in microseconds
+    pub time_usec: u64,
+    /// The number of fingers used for the gesture
+    pub finger_count: i32,
+    /// The delta on the x axis, with acceleration applied
+    pub dx: f64,
+    /// The delta on the y axis, with acceleration applied
+    pub dy: f64,
+    /// The unaccelerated delta on the x axis
+    pub dx_unaccelerated: f64,
+    /// The unaccelerated delta on the y axis
+    pub dy_unaccelerated: f64,
+    /// The absolute scale of the gesture, relative to the begin event
+    pub scale: f64,
+    /// The angle delta in degrees since the last update, clockwise positive
+    pub angle_delta: f64,
+}
+
+/// An owned copy of the end event of a pinch gesture
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct OwnedGesturePinchEndEvent {
+    /// The device the event comes from
+    pub device: Arc<DeviceInfo>,
+    /// The event time in microseconds
+    pub time_usec: u64,
+    /// The number of fingers used for the gesture
+    pub finger_count: i32,
+    /// The final absolute scale of the gesture
+    pub scale: f64,
+    /// The angle delta in degrees, clockwise positive
+    pub angle_delta: f64,
+    /// Whether the gesture was cancelled
+    pub cancelled: bool,
+}
+
+/// An owned copy of the end events of swipe and hold gestures
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct OwnedGestureEnd {
+    /// The device the event comes from
+    pub device: Arc<DeviceInfo>,
+    /// The event time in microseconds
+    pub time_usec: u64,
+    /// The number of fingers used for the gesture
+    pub finger_count: i32,
+    /// Whether the gesture was cancelled
+    pub cancelled: bool,
+}
+
+/// An owned copy of a [`GestureSwipeBeginEvent`](crate::event::GestureSwipeBeginEvent)
+pub type OwnedGestureSwipeBeginEvent = OwnedGestureBegin;
+/// An owned copy of a [`GestureHoldBeginEvent`](crate::event::GestureHoldBeginEvent)
+pub type OwnedGestureHoldBeginEvent = OwnedGestureBegin;
+/// An owned copy of a [`GestureSwipeEndEvent`](crate::event::GestureSwipeEndEvent)
+pub type OwnedGestureSwipeEndEvent = OwnedGestureEnd;
+/// An owned copy of a [`GestureHoldEndEvent`](crate::event::GestureHoldEndEvent)
+pub type OwnedGestureHoldEndEvent = OwnedGestureEnd;
+
+fn gesture_event(event: &GestureEvent, device: Arc<DeviceInfo>) -> OwnedGestureEvent {
+    macro_rules! begin {
+        ($e:ident) => {
+            OwnedGestureBegin {
+                device,
+                time_usec: $e.time_usec(),
+                finger_count: $e.finger_count(),
+            }
+        };
+    }
+
+    macro_rules! end {
+        ($e:ident) => {
+            OwnedGestureEnd {
+                device,
+                time_usec: $e.time_usec(),
+                finger_count: $e.finger_count(),
+                cancelled: $e.cancelled(),
+            }
+        };
+    }
+
+    match event {
+        GestureEvent::SwipeBegin(e) => OwnedGestureEvent::SwipeBegin(begin!(e)),
+        GestureEvent::SwipeUpdate(e) => {
+            OwnedGestureEvent::SwipeUpdate(OwnedGestureSwipeUpdateEvent {
+                device,
+                time_usec: e.time_usec(),
+                finger_count: e.finger_count(),
+                dx: e.dx(),
+                dy: e.dy(),
+                dx_unaccelerated: e.dx_unaccelerated(),
+                dy_unaccelerated: e.dy_unaccelerated(),
+            })
+        }
+        GestureEvent::SwipeEnd(e) => OwnedGestureEvent::SwipeEnd(end!(e)),
+        GestureEvent::PinchBegin(e) => OwnedGestureEvent::PinchBegin(OwnedGesturePinchBeginEvent {
+            device,
+            time_usec: e.time_usec(),
+            finger_count: e.finger_count(),
+            scale: e.scale(),
+            angle_delta: e.angle_delta(),
+        }),
+        GestureEvent::PinchUpdate(e) => {
+            OwnedGestureEvent::PinchUpdate(OwnedGesturePinchUpdateEvent {
+                device,
+                time_usec: e.time_usec(),
+                finger_count: e.finger_count(),
+                dx: e.dx(),
+                dy: e.dy(),
+                dx_unaccelerated: e.dx_unaccelerated(),
+                dy_unaccelerated: e.dy_unaccelerated(),
+                scale: e.scale(),
+                angle_delta: e.angle_delta(),
+            })
+        }
+        GestureEvent::PinchEnd(e) => OwnedGestureEvent::PinchEnd(OwnedGesturePinchEndEvent {
+            device,
+            time_usec: e.time_usec(),
+            finger_count: e.finger_count(),
+            scale: e.scale(),
+            angle_delta: e.angle_delta(),
+            cancelled: e.cancelled(),
+        }),
+        GestureEvent::HoldBegin(e) => OwnedGestureEvent::HoldBegin(begin!(e)),
+        GestureEvent::HoldEnd(e) => OwnedGestureEvent::HoldEnd(end!(e)),
+    }
+}
+
+owned_events!(
+    /// An owned copy of a [`KeyboardEvent`]
+    Keyboard,
+    /// See [`KeyboardKeyEvent`](crate::event::KeyboardKeyEvent)
+    Key,
+);
+
+owned_time!(OwnedKeyboardEvent, Key);
+
+/// An owned copy of a [`KeyboardKeyEvent`](crate::event::KeyboardKeyEvent)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct OwnedKeyboardKeyEvent {
+    /// The device the event comes from
+    pub device: Arc<DeviceInfo>,
+    /// The event time in microseconds
+    pub time_usec: u64,
+    /// The keycode that triggered the event
+    pub key: u32,
+    /// The state change of the key
+    pub key_state: KeyState,
+    /// The seat wide pressed key count for the key
+    pub seat_key_count: u32,
+}
+
+fn keyboard_event(event: &KeyboardEvent, device: Arc<DeviceInfo>) -> OwnedKeyboardEvent {
+    match event {
+        KeyboardEvent::Key(e) => OwnedKeyboardEvent::Key(OwnedKeyboardKeyEvent {
+            device,
+            time_usec: e.time_usec(),
+            key: e.key(),
+            key_state: e.key_state(),
+            seat_key_count: e.seat_key_count(),
+        }),
+    }
+}
+
+owned_events!(
+    /// An owned copy of a [`PointerEvent`]
+    Pointer,
+    /// See [`PointerMotionEvent`](crate::event::PointerMotionEvent)
+    Motion,
+    /// See [`PointerMotionAbsoluteEvent`](crate::event::PointerMotionAbsoluteEvent)
+    MotionAbsolute,
+    /// See [`PointerButtonEvent`](crate::event::PointerButtonEvent)
+    Button,
+    /// See [`PointerAxisEvent`](crate::event::PointerAxisEvent)
+    Axis,
+    /// See [`PointerScrollWheelEvent`](crate::event::PointerScrollWheelEvent)
+    ScrollWheel,
+    /// See [`PointerScrollFingerEvent`](crate::event::PointerScrollFingerEvent)
+    ScrollFinger,
+    /// See [`PointerScrollContinuousEvent`](crate::event::PointerScrollContinuousEvent)
+    ScrollContinuous,
+);
+
+owned_time!(
+    OwnedPointerEvent,
+    Motion,
+    MotionAbsolute,
+    Button,
+    Axis,
+    ScrollWheel,
+    ScrollFinger,
+    ScrollContinuous,
+);
+
+/// The value of each [`PointerAxis`], `None` for the axes the event has no value for
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AxisValues {
+    /// The value of [`PointerAxis::ScrollVertical`]
+    pub vertical: Option<f64>,
+    /// The value of [`PointerAxis::ScrollHorizontal`]
+    pub horizontal: Option<f64>,
+}
+
+impl AxisValues {
+    /// Returns the value of the given axis, if set
+    pub const fn get(&self, axis: PointerAxis) -> Option<f64> {
+        match axis {
+            PointerAxis::ScrollVertical => self.vertical,
+            PointerAxis::ScrollHorizontal => self.horizontal,
+        }
+    }
+
+    fn read(has_axis: impl Fn(PointerAxis) -> bool, value: impl Fn(PointerAxis) -> f64) -> Self {
+        let read = |axis| has_axis(axis).then(|| value(axis));
+
+        Self {
+            vertical: read(PointerAxis::ScrollVertical),
+            horizontal: read(PointerAxis::ScrollHorizontal),
+        }
+    }
+}
+
+/// An owned copy of a [`PointerMotionEvent`](crate::event::PointerMotionEvent)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct OwnedPointerMotionEvent {
+    /// The device the event comes from
+    pub device: Arc<DeviceInfo>,
+    /// The event time in microseconds
+    pub time_usec: u64,
+    /// The delta on the x axis, with acceleration applied
+    pub dx: f64,
+    /// The delta on the y axis, with acceleration applied
+    pub dy: f64,
+    /// The unaccelerated delta on the x axis, normalized to 1000dpi
+    pub dx_unaccelerated: f64,
+    /// The unaccelerated delta on the y axis, normalized to 1000dpi
+    pub dy_unaccelerated: f64,
+}
+
+/// An owned copy of a [`PointerMotionAbsoluteEvent`](crate::event::PointerMotionAbsoluteEvent)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct OwnedPointerMotionAbsoluteEvent {
+    /// The device the event comes from
+    pub device: Arc<DeviceInfo>,
+    /// The event time in microseconds
+    pub time_usec: u64,
+    /// The absolute x coordinate in mm from the top left corner of the device
+    pub absolute_x: f64,
+    /// The absolute y coordinate in mm from the top left corner of the device
+    pub absolute_y: f64,
+}
+
+/// An owned copy of a [`PointerButtonEvent`](crate::event::PointerButtonEvent)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct OwnedPointerButtonEvent {
+    /// The device the event comes from
+    pub device: Arc<DeviceInfo>,
+    /// The event time in microseconds
+    pub time_usec: u64,
+    /// The button that triggered the event, see `linux/input-event-codes.h`
+    pub button: u32,
+    /// The state of the button
+    pub button_state: ButtonState,
+    /// The total number of buttons pressed on the seat after the event
+    pub seat_button_count: u32,
+}
+
+/// An owned copy of a [`PointerAxisEvent`](crate::event::PointerAxisEvent)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct OwnedPointerAxisEvent {
+    /// The device the event comes from
+    pub device: Arc<DeviceInfo>,
+    /// The event time in microseconds
+    pub time_usec: u64,
+    /// The source of the event
+    pub axis_source: AxisSource,
+    /// The value of the axes set by the event
+    pub values: AxisValues,
+    /// The value of the axes set by the event in discrete steps, only meaningful for wheel sources
+    pub values_discrete: AxisValues,
+}
+
+/// An owned copy of a [`PointerScrollWheelEvent`](crate::event::PointerScrollWheelEvent)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct OwnedPointerScrollWheelEvent {
+    /// The device the event comes from
+    pub device: Arc<DeviceInfo>,
+    /// The event time in microseconds
+    pub time_usec: u64,
+    /// The scroll value of the axes set by the event
+    pub values: AxisValues,
+    /// The scroll value of the axes set by the event, where 120 is one logical wheel detent
+    pub values_v120: AxisValues,
+}
+
+/// An owned copy of the finger and continuous scroll events
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct OwnedPointerScroll {
+    /// The device the event comes from
+    pub device: Arc<DeviceInfo>,
+    /// The event time in microseconds
+    pub time_usec: u64,
+    /// The scroll value of the axes set by the event
+    pub values: AxisValues,
+}
+
+/// An owned copy of a [`PointerScrollFingerEvent`](crate::event::PointerScrollFingerEvent)
+pub type OwnedPointerScrollFingerEvent = OwnedPointerScroll;
+/// An owned copy of a [`PointerScrollContinuousEvent`](crate::event::PointerScrollContinuousEvent)
+pub type OwnedPointerScrollContinuousEvent = OwnedPointerScroll;
+
+fn pointer_event(event: &PointerEvent, device: Arc<DeviceInfo>) -> OwnedPointerEvent {
+    macro_rules! values {
+        ($e:ident, $value:ident) => {
+            AxisValues::read(|axis| $e.has_axis(axis), |axis| $e.$value(axis))
+        };
+    }
+
+    match event {
+        PointerEvent::Motion(e) => OwnedPointerEvent::Motion(OwnedPointerMotionEvent {
+            device,
+            time_usec: e.time_usec(),
+            dx: e.dx(),
+            dy: e.dy(),
+            dx_unaccelerated: e.dx_unaccelerated(),
+            dy_unaccelerated: e.dy_unaccelerated(),
+        }),
+        PointerEvent::MotionAbsolute(e) => {
+            OwnedPointerEvent::MotionAbsolute(OwnedPointerMotionAbsoluteEvent {
+                device,
+                time_usec: e.time_usec(),
+                absolute_x: e.absolute_x(),
+                absolute_y: e.absolute_y(),
+            })
+        }
+        PointerEvent::Button(e) => OwnedPointerEvent::Button(OwnedPointerButtonEvent {
+            device,
+            time_usec: e.time_usec(),
+            button: e.button(),
+            button_state: e.button_state(),
+            seat_button_count: e.seat_button_count(),
+        }),
+        PointerEvent::Axis(e) => OwnedPointerEvent::Axis(OwnedPointerAxisEvent {
+            device,
+            time_usec: e.time_usec(),
+            axis_source: e.axis_source(),
+            values: values!(e, axis_value),
+            values_discrete: values!(e, axis_value_discrete),
+        }),
+        PointerEvent::ScrollWheel(e) => {
+            OwnedPointerEvent::ScrollWheel(OwnedPointerScrollWheelEvent {
+                device,
+                time_usec: e.time_usec(),
+                values: values!(e, scroll_value),
+                values_v120: values!(e, scroll_value_v120),
+            })
+        }
+        PointerEvent::ScrollFinger(e) => OwnedPointerEvent::ScrollFinger(OwnedPointerScroll {
+            device,
+            time_usec: e.time_usec(),
+            values: values!(e, scroll_value),
+        }),
+        PointerEvent::ScrollContinuous(e) => {
+            OwnedPointerEvent::ScrollContinuous(OwnedPointerScroll {
+                device,
+                time_usec: e.time_usec(),
+                values: values!(e, scroll_value),
+            })
+        }
+    }
+}
+
+owned_events!(
+    /// An owned copy of a [`SwitchEvent`]
+    Switch,
+    /// See [`SwitchToggleEvent`](crate::event::SwitchToggleEvent)
+    Toggle,
+);
+
+owned_time!(OwnedSwitchEvent, Toggle);
+
+/// An owned copy of a [`SwitchToggleEvent`](crate::event::SwitchToggleEvent)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct OwnedSwitchToggleEvent {
+    /// The device the event comes from
+    pub device: Arc<DeviceInfo>,
+    /// The event time in microseconds
+    pub time_usec: u64,
+    /// The switch that triggered the event
+    pub switch: Switch,
+    /// The new state of the switch
+    pub switch_state: SwitchState,
+}
+
+fn switch_event(event: &SwitchEvent, device: Arc<DeviceInfo>) -> OwnedSwitchEvent {
+    match event {
+        SwitchEvent::Toggle(e) => OwnedSwitchEvent::Toggle(OwnedSwitchToggleEvent {
+            device,
+            time_usec: e.time_usec(),
+            switch: e.switch(),
+            switch_state: e.switch_state(),
+        }),
+    }
+}
+
+owned_events!(
+    /// An owned copy of a [`TabletPadEvent`]
+    TabletPad,
+    /// See [`TabletPadButtonEvent`](crate::event::TabletPadButtonEvent)
+    Button,
+    /// See [`TabletPadRingEvent`](crate::event::TabletPadRingEvent)
+    Ring,
+    /// See [`TabletPadStripEvent`](crate::event::TabletPadStripEvent)
+    Strip,
+    /// See [`TabletPadKeyEvent`](crate::event::TabletPadKeyEvent)
+    Key,
+    /// See [`TabletPadDialEvent`](crate::event::TabletPadDialEvent)
+    Dial,
+);
+
+owned_time!(OwnedTabletPadEvent, Button, Ring, Strip, Key, Dial);
+
+/// An owned copy of a [`TabletPadButtonEvent`](crate::event::TabletPadButtonEvent)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct OwnedTabletPadButtonEvent {
+    /// The device the event comes from
+    pub device: Arc<DeviceInfo>,
+    /// The event time in microseconds
+    pub time_usec: u64,
+    /// The button number that triggered the event, starting at 0
+    pub button_number: u32,
+    /// The state of the button
+    pub button_state: ButtonState,
+    /// The mode the button is in
+    pub mode: u32,
+}
+
+/// An owned copy of the ring and strip events
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct OwnedTabletPadAxis {
+    /// The device the event comes from
+    pub device: Arc<DeviceInfo>,
+    /// The event time in microseconds
+    pub time_usec: u64,
+    /// The number of the ring or strip that changed state, starting at 0
+    pub number: u32,
+    /// The current position, in degrees for rings and normalized to [0, 1] for strips, or -1 if the finger was lifted
+    pub position: f64,
+    /// The source of the interaction
+    pub source: PadAxisSource,
+    /// The mode the ring or strip is in
+    pub mode: u32,
+}
+
+/// An owned copy of a [`TabletPadRingEvent`](crate::event::TabletPadRingEvent)
+pub type OwnedTabletPadRingEvent = OwnedTabletPadAxis;
+/// An owned copy of a [`TabletPadStripEvent`](crate::event::TabletPadStripEvent)
+pub type OwnedTabletPadStripEvent = OwnedTabletPadAxis;
+
+/// An owned copy of a [`TabletPadKeyEvent`](crate::event::TabletPadKeyEvent)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct OwnedTabletPadKeyEvent {
+    /// The device the event comes from
+    pub device: Arc<DeviceInfo>,
+    /// The event time in microseconds
+    pub time_usec: u64,
+    /// The key code that triggered the event, see `linux/input-event-codes.h`
+    pub key: u32,
+    /// The state of the key
+    pub key_state: KeyState,
+}
+
+/// An owned copy of a [`TabletPadDialEvent`](crate::event::TabletPadDialEvent)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct OwnedTabletPadDialEvent {
+    /// The device the event comes from
+    pub device: Arc<DeviceInfo>,
+    /// The event time in microseconds
+    pub time_usec: u64,
+}
+
+fn tablet_pad_event(event: &TabletPadEvent, device: Arc<DeviceInfo>) -> OwnedTabletPadEvent {
+    match event {
+        TabletPadEvent::Button(e) => OwnedTabletPadEvent::Button(OwnedTabletPadButtonEvent {
+            device,
+            time_usec: e.time_usec(),
+            button_number: e.button_number(),
+            button_state: e.button_state(),
+            mode: e.mode(),
+        }),
+        TabletPadEvent::Ring(e) => OwnedTabletPadEvent::Ring(OwnedTabletPadAxis {
+            device,
+            time_usec: e.time_usec(),
+            number: e.ring_number(),
+            position: e.position(),
+            source: e.source(),
+            mode: e.mode(),
+        }),
+        TabletPadEvent::Strip(e) => OwnedTabletPadEvent::Strip(OwnedTabletPadAxis {
+            device,
+            time_usec: e.time_usec(),
+            number: e.strip_number(),
+            position: e.position(),
+            source: e.source(),
+            mode: e.mode(),
+        }),
+        TabletPadEvent::Key(e) => OwnedTabletPadEvent::Key(OwnedTabletPadKeyEvent {
+            device,
+            time_usec: e.time_usec(),
+            key: e.key(),
+            key_state: e.key_state(),
+        }),
+        TabletPadEvent::Dial(e) => OwnedTabletPadEvent::Dial(OwnedTabletPadDialEvent {
+            device,
+            time_usec: e.time_usec(),
+        }),
+    }
+}
+
+owned_events!(
+    /// An owned copy of a [`TabletToolEvent`]
+    TabletTool,
+    /// See [`TabletToolAxisEvent`](crate::event::TabletToolAxisEvent)
+    Axis,
+    /// See [`TabletToolProximityEvent`](crate::event::TabletToolProximityEvent)
+    Proximity,
+    /// See [`TabletToolTipEvent`](crate::event::TabletToolTipEvent)
+    Tip,
+    /// See [`TabletToolButtonEvent`](crate::event::TabletToolButtonEvent)
+    Button,
+);
+
+owned_time!(OwnedTabletToolEvent, Axis, Proximity, Tip, Button);
+
+/// The tool of a tablet tool event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct TabletTool {
+    /// The type of the tool
+    pub tool_type: TabletToolType,
+    /// The serial number of the tool, 0 if the tool does not report one
+    pub serial: u64,
+    /// The hardware id of the tool, 0 if the tool does not report one
+    pub id: u64,
+}
+
+/// Which axes of a tablet tool changed in an event
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+#[allow(missing_docs)] // Named after the axes of `TabletToolAxes`
+pub struct ChangedAxes {
+    pub x: bool,
+    pub y: bool,
+    pub pressure: bool,
+    pub distance: bool,
+    pub tilt_x: bool,
+    pub tilt_y: bool,
+    pub rotation: bool,
+    pub slider: bool,
+    pub size_major: bool,
+    pub size_minor: bool,
+    pub wheel: bool,
+}
+
+/// The axes of a tablet tool at the time of an event. Axes the tool does not have are 0.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct TabletToolAxes {
+    /// The x coordinate in mm from the top left corner of the tablet
+    pub x: f64,
+    /// The y coordinate in mm from the top left corner of the tablet
+    pub y: f64,
+    /// The delta on the x axis since the last event
+    pub dx: f64,
+    /// The delta on the y axis since the last event
+    pub dy: f64,
+    /// The pressure, normalized to [0, 1]
+    pub pressure: f64,
+    /// The distance to the tablet, normalized to [0, 1]
+    pub distance: f64,
+    /// The tilt on the x axis in degrees
+    pub tilt_x: f64,
+    /// The tilt on the y axis in degrees
+    pub tilt_y: f64,
+    /// The z rotation in degrees
+    pub rotation: f64,
+    /// The slider position, normalized to [-1, 1]
+    pub slider_position: f64,
+    /// The major axis of the touching ellipse in mm
+    pub size_major: f64,
+    /// The minor axis of the touching ellipse in mm
+    pub size_minor: f64,
+    /// The wheel delta in degrees
+    pub wheel_delta: f64,
+    /// The wheel delta in discrete steps
+    pub wheel_delta_discrete: i32,
+    /// The axes that changed in this event
+    pub changed: ChangedAxes,
+}
+
+/// An owned copy of a [`TabletToolAxisEvent`](crate::event::TabletToolAxisEvent)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct OwnedTabletToolAxisEvent {
+    /// The device the event comes from
+    pub device: Arc<DeviceInfo>,
+    /// The event time in microseconds
+    pub time_usec: u64,
+    /// The tool that triggered the event
+    pub tool: TabletTool,
+    /// The axes of the tool
+    pub axes: TabletToolAxes,
+}
+
+/// An owned copy of a [`TabletToolProximityEvent`](crate::event::TabletToolProximityEvent)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct OwnedTabletToolProximityEvent {
+    /// The device the event comes from
+    pub device: Arc<DeviceInfo>,
+    /// The event time in microseconds
+    pub time_usec: u64,
+    /// The tool that triggered the event
+    pub tool: TabletTool,
+    /// The axes of the tool
+    pub axes: TabletToolAxes,
+    /// The new proximity state of the tool
+    pub proximity_state: ProximityState,
+}
+
+/// An owned copy of a [`TabletToolTipEvent`](crate::event::TabletToolTipEvent)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct OwnedTabletToolTipEvent {
+    /// The device the event comes from
+    pub device: Arc<DeviceInfo>,
+    /// The event time in microseconds
+    pub time_usec: u64,
+    /// The tool that triggered the event
+    pub tool: TabletTool,
+    /// The axes of the tool
+    pub axes: TabletToolAxes,
+    /// The new tip state of the tool
+    pub tip_state: TipState,
+}
+
+/// An owned copy of a [`TabletToolButtonEvent`](crate::event::TabletToolButtonEvent)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct OwnedTabletToolButtonEvent {
+    /// The device the event comes from
+    pub device: Arc<DeviceInfo>,
+    /// The event time in microseconds
+    pub time_usec: u64,
+    /// The tool that triggered the event
+    pub tool: TabletTool,
+    /// The axes of the tool
+    pub axes: TabletToolAxes,
+    /// The button that triggered the event, see `linux/input-event-codes.h`
+    pub button: u32,
+    /// The state of the button
+    pub button_state: ButtonState,
+    /// The total number of buttons pressed on the seat after the event
+    pub seat_button_count: u32,
+}
+
+fn tablet_tool_event(event: &TabletToolEvent, device: Arc<DeviceInfo>) -> OwnedTabletToolEvent {
+    macro_rules! tool {
+        ($e:ident) => {
+            TabletTool {
+                tool_type: $e.tool_type(),
+                serial: $e.tool_serial(),
+                id: $e.tool_id(),
+            }
+        };
+    }
+
+    macro_rules! axes {
+        ($e:ident) => {
+            TabletToolAxes {
+                x: $e.x(),
+                y: $e.y(),
+                dx: $e.dx(),
+                dy: $e.dy(),
+                pressure: $e.pressure(),
+                distance: $e.distance(),
+                tilt_x: $e.tilt_x(),
+                tilt_y: $e.tilt_y(),
+                rotation: $e.rotation(),
+                slider_position: $e.slider_position(),
+                size_major: $e.size_major(),
+                size_minor: $e.size_minor(),
+                wheel_delta: $e.wheel_delta(),
+                wheel_delta_discrete: $e.wheel_delta_discrete(),
+                changed: ChangedAxes {
+                    x: $e.x_has_changed(),
+                    y: $e.y_has_changed(),
+                    pressure: $e.pressure_has_changed(),
+                    distance: $e.distance_has_changed(),
+                    tilt_x: $e.tilt_x_has_changed(),
+                    tilt_y: $e.tilt_y_has_changed(),
+                    rotation: $e.rotation_has_changed(),
+                    slider: $e.slider_has_changed(),
+                    size_major: $e.size_major_has_changed(),
+                    size_minor: $e.size_minor_has_changed(),
+                    wheel: $e.wheel_has_changed(),
+                },
+            }
+        };
+    }
+
+    match event {
+        TabletToolEvent::Axis(e) => OwnedTabletToolEvent::Axis(OwnedTabletToolAxisEvent {
+            device,
+            time_usec: e.time_usec(),
+            tool: tool!(e),
+            axes: axes!(e),
+        }),
+        TabletToolEvent::Proximity(e) => {
+            OwnedTabletToolEvent::Proximity(OwnedTabletToolProximityEvent {
+                device,
+                time_usec: e.time_usec(),
+                tool: tool!(e),
+                axes: axes!(e),
+                proximity_state: e.proximity_state(),
+            })
+        }
+        TabletToolEvent::Tip(e) => OwnedTabletToolEvent::Tip(OwnedTabletToolTipEvent {
+            device,
+            time_usec: e.time_usec(),
+            tool: tool!(e),
+            axes: axes!(e),
+            tip_state: e.tip_state(),
+        }),
+        TabletToolEvent::Button(e) => OwnedTabletToolEvent::Button(OwnedTabletToolButtonEvent {
+            device,
+            time_usec: e.time_usec(),
+            tool: tool!(e),
+            axes: axes!(e),
+            button: e.button(),
+            button_state: e.button_state(),
+            seat_button_count: e.seat_button_count(),
+        }),
+    }
+}
+
+owned_events!(
+    /// An owned copy of a [`TouchEvent`]
+    Touch,
+    /// See [`TouchDownEvent`](crate::event::TouchDownEvent)
+    Down,
+    /// See [`TouchUpEvent`](crate::event::TouchUpEvent)
+    Up,
+    /// See [`TouchMotionEvent`](crate::event::TouchMotionEvent)
+    Motion,
+    /// See [`TouchCancelEvent`](crate::event::TouchCancelEvent)
+    Cancel,
+    /// See [`TouchFrameEvent`](crate::event::TouchFrameEvent)
+    Frame,
+);
+
+owned_time!(OwnedTouchEvent, Down, Up, Motion, Cancel, Frame);
+
+/// An owned copy of the down and motion touch events
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct OwnedTouchPoint {
+    /// The device the event comes from
+    pub device: Arc<DeviceInfo>,
+    /// The event time in microseconds
+    pub time_usec: u64,
+    /// The slot of the touch point, unique within the device
+    pub slot: i32,
+    /// The slot of the touch point, unique within the seat
+    pub seat_slot: i32,
+    /// The x coordinate in mm from the top left corner of the device
+    pub x: f64,
+    /// The y coordinate in mm from the top left corner of the device
+    pub y: f64,
+}
+
+/// An owned copy of the up and cancel touch events
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct OwnedTouchSlot {
+    /// The device the event comes from
+    pub device: Arc<DeviceInfo>,
+    /// The event time in microseconds
+    pub time_usec: u64,
+    /// The slot of the touch point, unique within the device
+    pub slot: i32,
+    /// The slot of the touch point, unique within the seat
+    pub seat_slot: i32,
+}
+
+/// An owned copy of a [`TouchFrameEvent`](crate::event::TouchFrameEvent)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct OwnedTouchFrameEvent {
+    /// The device the event comes from
+    pub device: Arc<DeviceInfo>,
+    /// The event time in microseconds
+    pub time_usec: u64,
+}
+
+/// An owned copy of a [`TouchDownEvent`](crate::event::TouchDownEvent)
+pub type OwnedTouchDownEvent = OwnedTouchPoint;
+/// An owned copy of a [`TouchMotionEvent`](crate::event::TouchMotionEvent)
+pub type OwnedTouchMotionEvent = OwnedTouchPoint;
+/// An owned copy of a [`TouchUpEvent`](crate::event::TouchUpEvent)
+pub type OwnedTouchUpEvent = OwnedTouchSlot;
+/// An owned copy of a [`TouchCancelEvent`](crate::event::TouchCancelEvent)
+pub type OwnedTouchCancelEvent = OwnedTouchSlot;
+
+fn touch_event(event: &TouchEvent, device: Arc<DeviceInfo>) -> OwnedTouchEvent {
+    macro_rules! point {
+        ($e:ident) => {
+            OwnedTouchPoint {
+                device,
+                time_usec: $e.time_usec(),
+                slot: $e.slot(),
+                seat_slot: $e.seat_slot(),
+                x: $e.x(),
+                y: $e.y(),
+            }
+        };
+    }
+
+    macro_rules! slot {
+        ($e:ident) => {
+            OwnedTouchSlot {
+                device,
+                time_usec: $e.time_usec(),
+                slot: $e.slot(),
+                seat_slot: $e.seat_slot(),
+            }
+        };
+    }
+
+    match event {
+        TouchEvent::Down(e) => OwnedTouchEvent::Down(point!(e)),
+        TouchEvent::Up(e) => OwnedTouchEvent::Up(slot!(e)),
+        TouchEvent::Motion(e) => OwnedTouchEvent::Motion(point!(e)),
+        TouchEvent::Cancel(e) => OwnedTouchEvent::Cancel(slot!(e)),
+        TouchEvent::Frame(e) => OwnedTouchEvent::Frame(OwnedTouchFrameEvent {
+            device,
+            time_usec: e.time_usec(),
+        }),
+    }
+}
+
+/// An owned copy of an [`Unknown`] event
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct OwnedUnknown {
+    /// The device the event comes from
+    pub device: Arc<DeviceInfo>,
+    /// The raw libinput event type
+    pub raw_type: u32,
+}
+
+fn unknown_event(event: &Unknown, device: Arc<DeviceInfo>) -> OwnedUnknown {
+    OwnedUnknown {
+        device,
+        raw_type: unsafe { sys::libinput_event_get_type(event.as_raw_event()) },
+    }
+}
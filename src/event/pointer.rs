@@ -2,9 +2,12 @@
 
 //! Pointer events reflect motion, button and scroll events, as well as events from other axes.
 
-use crate::sys::{
-    self, libinput_event_get_pointer_event, libinput_event_pointer,
-    libinput_event_pointer_get_base_event,
+use crate::{
+    event::{DescribeEvent, EventFields, impl_time},
+    sys::{
+        self, libinput_event_get_pointer_event, libinput_event_pointer,
+        libinput_event_pointer_get_base_event,
+    },
 };
 
 super::define_events!(
@@ -13,6 +16,7 @@ super::define_events!(
     libinput_event_pointer,
     libinput_event_pointer_get_base_event,
     libinput_event_get_pointer_event,
+    Some(sys::libinput_event_pointer_get_time_usec),
     Motion,
     MotionAbsolute,
     Button,
@@ -22,6 +26,40 @@ super::define_events!(
     ScrollContinuous,
 );
 
+impl_time!(
+    sys::libinput_event_pointer_get_time,
+    sys::libinput_event_pointer_get_time_usec,
+    PointerMotionEvent,
+    PointerMotionAbsoluteEvent,
+    PointerButtonEvent,
+    PointerAxisEvent,
+    PointerScrollWheelEvent,
+    PointerScrollFingerEvent,
+    PointerScrollContinuousEvent,
+);
+
+impl PointerMotionEvent {
+    /// The delta between the last event and the current event on the x axis, with acceleration applied.
+    pub fn dx(&self) -> f64 {
+        unsafe { sys::libinput_event_pointer_get_dx(self.raw) }
+    }
+
+    /// The delta between the last event and the current event on the y axis, with acceleration applied.
+    pub fn dy(&self) -> f64 {
+        unsafe { sys::libinput_event_pointer_get_dy(self.raw) }
+    }
+
+    /// The relative delta of the unaccelerated motion vector on the x axis, normalized to 1000dpi.
+    pub fn dx_unaccelerated(&self) -> f64 {
+        unsafe { sys::libinput_event_pointer_get_dx_unaccelerated(self.raw) }
+    }
+
+    /// The relative delta of the unaccelerated motion vector on the y axis, normalized to 1000dpi.
+    pub fn dy_unaccelerated(&self) -> f64 {
+        unsafe { sys::libinput_event_pointer_get_dy_unaccelerated(self.raw) }
+    }
+}
+
 impl PointerMotionAbsoluteEvent {
     /// Tthe current absolute x coordinate of the pointer event, in mm from the top left corner of the device.
     /// To get the corresponding output screen coordinate, use [`absolute_x_transformed`](Self::absolute_x_transformed).
@@ -45,3 +83,249 @@ impl PointerMotionAbsoluteEvent {
         unsafe { sys::libinput_event_pointer_get_absolute_y_transformed(self.raw, width) }
     }
 }
+
+impl PointerButtonEvent {
+    /// The button that triggered this event, see `linux/input-event-codes.h`
+    pub fn button(&self) -> u32 {
+        unsafe { sys::libinput_event_pointer_get_button(self.raw) }
+    }
+
+    /// The button state that triggered this event
+    pub fn button_state(&self) -> ButtonState {
+        ButtonState::from_raw(unsafe { sys::libinput_event_pointer_get_button_state(self.raw) })
+    }
+
+    /// The total number of buttons pressed on all devices on the associated seat after the event was triggered.
+    pub fn seat_button_count(&self) -> u32 {
+        unsafe { sys::libinput_event_pointer_get_seat_button_count(self.raw) }
+    }
+}
+
+/// Generates the accessors shared by the axis and scroll events
+macro_rules! impl_axis {
+    ($($event:ident),+ $(,)?) => {
+        $(
+            impl $event {
+                /// Check if the event has a valid value for the given axis.
+                pub fn has_axis(&self, axis: PointerAxis) -> bool {
+                    unsafe { sys::libinput_event_pointer_has_axis(self.raw, axis.as_raw()) != 0 }
+                }
+            }
+        )+
+    };
+}
+
+impl_axis!(
+    PointerAxisEvent,
+    PointerScrollWheelEvent,
+    PointerScrollFingerEvent,
+    PointerScrollContinuousEvent,
+);
+
+impl PointerAxisEvent {
+    /// The axis value of the given axis. Use [`has_axis`](Self::has_axis) to check if the axis is set.
+    pub fn axis_value(&self, axis: PointerAxis) -> f64 {
+        unsafe { sys::libinput_event_pointer_get_axis_value(self.raw, axis.as_raw()) }
+    }
+
+    /// The source of the axis event
+    pub fn axis_source(&self) -> AxisSource {
+        AxisSource::from_raw(unsafe { sys::libinput_event_pointer_get_axis_source(self.raw) })
+    }
+
+    /// The axis value in discrete steps for a given axis event, only valid for wheel sources.
+    pub(crate) fn axis_value_discrete(&self, axis: PointerAxis) -> f64 {
+        unsafe { sys::libinput_event_pointer_get_axis_value_discrete(self.raw, axis.as_raw()) }
+    }
+}
+
+/// Generates the `scroll_value` accessor shared by the scroll events
+macro_rules! impl_scroll {
+    ($($event:ident),+ $(,)?) => {
+        $(
+            impl $event {
+                /// The axis value of the given axis. Use [`has_axis`](Self::has_axis) to check if the axis is set.
+                pub fn scroll_value(&self, axis: PointerAxis) -> f64 {
+                    unsafe { sys::libinput_event_pointer_get_scroll_value(self.raw, axis.as_raw()) }
+                }
+            }
+        )+
+    };
+}
+
+impl_scroll!(
+    PointerScrollWheelEvent,
+    PointerScrollFingerEvent,
+    PointerScrollContinuousEvent,
+);
+
+impl PointerScrollWheelEvent {
+    /// The axis value of the given axis in a normalized form where 120 is one logical wheel detent.
+    pub(crate) fn scroll_value_v120(&self, axis: PointerAxis) -> f64 {
+        unsafe { sys::libinput_event_pointer_get_scroll_value_v120(self.raw, axis.as_raw()) }
+    }
+}
+
+impl DescribeEvent for PointerMotionEvent {
+    fn describe(&self, fields: &mut EventFields<'_, '_>) {
+        fields
+            .field("dx", &self.dx())
+            .field("dy", &self.dy())
+            .field("dx_unaccelerated", &self.dx_unaccelerated())
+            .field("dy_unaccelerated", &self.dy_unaccelerated());
+    }
+}
+
+impl DescribeEvent for PointerMotionAbsoluteEvent {
+    fn describe(&self, fields: &mut EventFields<'_, '_>) {
+        fields
+            .field("x", &self.absolute_x())
+            .field("y", &self.absolute_y());
+    }
+}
+
+impl DescribeEvent for PointerButtonEvent {
+    fn describe(&self, fields: &mut EventFields<'_, '_>) {
+        fields
+            .field("button", &self.button())
+            .field("state", &self.button_state())
+            .field("seat_button_count", &self.seat_button_count());
+    }
+}
+
+impl DescribeEvent for PointerAxisEvent {
+    fn describe(&self, fields: &mut EventFields<'_, '_>) {
+        fields.field("source", &self.axis_source());
+
+        for axis in PointerAxis::ALL {
+            if self.has_axis(axis) {
+                fields.field(axis.name(), &self.axis_value(axis));
+            }
+        }
+    }
+}
+
+/// Generates the formatting shared by the scroll events
+macro_rules! describe_scroll {
+    ($($event:ident),+ $(,)?) => {
+        $(
+            impl DescribeEvent for $event {
+                fn describe(&self, fields: &mut EventFields<'_, '_>) {
+                    for axis in PointerAxis::ALL {
+                        if self.has_axis(axis) {
+                            fields.field(axis.name(), &self.scroll_value(axis));
+                        }
+                    }
+                }
+            }
+        )+
+    };
+}
+
+describe_scroll!(
+    PointerScrollWheelEvent,
+    PointerScrollFingerEvent,
+    PointerScrollContinuousEvent,
+);
+
+/// Logical state of a physical button. Note that the logical state may not represent the physical state of the button.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum ButtonState {
+    /// Logical released state
+    Released,
+    /// Logical pressed state
+    Pressed,
+    /// A button state not known to this version of colpetto
+    Unknown(u32),
+}
+
+impl std::fmt::Display for ButtonState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Released => write!(f, "released"),
+            Self::Pressed => write!(f, "pressed"),
+            Self::Unknown(raw) => write!(f, "unknown ({raw})"),
+        }
+    }
+}
+
+impl ButtonState {
+    /// Builds a new button state from a raw libinput one
+    pub const fn from_raw(raw: sys::libinput_button_state) -> Self {
+        match raw {
+            sys::libinput_button_state::LIBINPUT_BUTTON_STATE_RELEASED => Self::Released,
+            sys::libinput_button_state::LIBINPUT_BUTTON_STATE_PRESSED => Self::Pressed,
+            sys::libinput_button_state(raw) => Self::Unknown(raw),
+        }
+    }
+}
+
+/// Axes on a device with the pointer capability that are not x or y coordinates.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PointerAxis {
+    /// The vertical scroll axis
+    ScrollVertical,
+    /// The horizontal scroll axis
+    ScrollHorizontal,
+}
+
+impl PointerAxis {
+    /// Every available axis
+    pub const ALL: [Self; 2] = [Self::ScrollVertical, Self::ScrollHorizontal];
+
+    /// Returns the raw libinput axis
+    pub const fn as_raw(self) -> sys::libinput_pointer_axis {
+        match self {
+            Self::ScrollVertical => {
+                sys::libinput_pointer_axis::LIBINPUT_POINTER_AXIS_SCROLL_VERTICAL
+            }
+            Self::ScrollHorizontal => {
+                sys::libinput_pointer_axis::LIBINPUT_POINTER_AXIS_SCROLL_HORIZONTAL
+            }
+        }
+    }
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::ScrollVertical => "vertical",
+            Self::ScrollHorizontal => "horizontal",
+        }
+    }
+}
+
+/// The source for a [`PointerAxisEvent`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum AxisSource {
+    /// The event is caused by the rotation of a wheel
+    Wheel,
+    /// The event is caused by the movement of one or more fingers on a device
+    Finger,
+    /// The event is caused by the motion of some device
+    Continuous,
+    /// The event is caused by the tilting of a mouse wheel rather than its rotation
+    WheelTilt,
+    /// An axis source not known to this version of colpetto
+    Unknown(u32),
+}
+
+impl AxisSource {
+    /// Builds a new axis source from a raw libinput one
+    pub const fn from_raw(raw: sys::libinput_pointer_axis_source) -> Self {
+        match raw {
+            sys::libinput_pointer_axis_source::LIBINPUT_POINTER_AXIS_SOURCE_WHEEL => Self::Wheel,
+            sys::libinput_pointer_axis_source::LIBINPUT_POINTER_AXIS_SOURCE_FINGER => Self::Finger,
+            sys::libinput_pointer_axis_source::LIBINPUT_POINTER_AXIS_SOURCE_CONTINUOUS => {
+                Self::Continuous
+            }
+            sys::libinput_pointer_axis_source::LIBINPUT_POINTER_AXIS_SOURCE_WHEEL_TILT => {
+                Self::WheelTilt
+            }
+            sys::libinput_pointer_axis_source(raw) => Self::Unknown(raw),
+        }
+    }
+}
@@ -6,9 +6,12 @@
 //! These switches can trigger events when the physical state of the device changes,
 //! for example when a laptop lid is closed.
 
-use crate::sys::{
-    self, libinput_event_get_switch_event, libinput_event_switch,
-    libinput_event_switch_get_base_event,
+use crate::{
+    event::{DescribeEvent, EventFields, impl_time},
+    sys::{
+        self, libinput_event_get_switch_event, libinput_event_switch,
+        libinput_event_switch_get_base_event,
+    },
 };
 
 super::define_events!(
@@ -21,6 +24,7 @@ super::define_events!(
     libinput_event_switch,
     libinput_event_switch_get_base_event,
     libinput_event_get_switch_event,
+    Some(sys::libinput_event_switch_get_time_usec),
     /// Signals that the switch has been toggled between states.
     ///
     /// This event is sent when a switch changes its physical state.
@@ -28,7 +32,18 @@ super::define_events!(
     Toggle,
 );
 
+impl_time!(
+    sys::libinput_event_switch_get_time,
+    sys::libinput_event_switch_get_time_usec,
+    SwitchToggleEvent,
+);
+
 impl SwitchToggleEvent {
+    /// Returns the switch that triggered this event.
+    pub fn switch(&self) -> Switch {
+        Switch::from_raw(unsafe { sys::libinput_event_switch_get_switch(self.raw) })
+    }
+
     /// Returns the current state of the switch.
     ///
     /// This function returns whether the switch is currently in the on or off position.
@@ -37,16 +52,63 @@ impl SwitchToggleEvent {
     }
 }
 
+impl DescribeEvent for SwitchToggleEvent {
+    fn describe(&self, fields: &mut EventFields<'_, '_>) {
+        fields
+            .field("switch", &self.switch())
+            .field("state", &self.switch_state());
+    }
+}
+
+/// The type of a switch.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Switch {
+    /// The laptop lid was closed when the switch state is on, or was opened when it is off.
+    Lid,
+    /// This switch indicates whether the device is in normal laptop mode or behaves like a tablet-like device
+    /// where the primary interaction is usually a touch screen.
+    TabletMode,
+    /// A switch not known to this version of colpetto
+    Unknown(u32),
+}
+
+impl std::fmt::Display for Switch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Lid => write!(f, "lid"),
+            Self::TabletMode => write!(f, "tablet-mode"),
+            Self::Unknown(raw) => write!(f, "unknown ({raw})"),
+        }
+    }
+}
+
+impl Switch {
+    /// Builds a new switch from a raw libinput one
+    pub const fn from_raw(raw: sys::libinput_switch) -> Self {
+        match raw {
+            sys::libinput_switch::LIBINPUT_SWITCH_LID => Self::Lid,
+            sys::libinput_switch::LIBINPUT_SWITCH_TABLET_MODE => Self::TabletMode,
+            sys::libinput_switch(raw) => Self::Unknown(raw),
+        }
+    }
+}
+
 /// Represents the physical state of a switch device.
 ///
 /// A switch can be in one of two states: on or off, reflecting the physical
 /// position of the switch.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub enum SwitchState {
     /// The switch is in the off position.
     Off,
     /// The switch is in the on position.
     On,
+    /// A switch state not known to this version of colpetto
+    Unknown(u32),
 }
 
 impl std::fmt::Display for SwitchState {
@@ -54,21 +116,18 @@ impl std::fmt::Display for SwitchState {
         match self {
             Self::Off => write!(f, "off"),
             Self::On => write!(f, "on"),
+            Self::Unknown(raw) => write!(f, "unknown ({raw})"),
         }
     }
 }
 
 impl SwitchState {
-    /// Builds a new switch state from a raw libinput switch state.
-    ///
-    /// # Panics
-    ///
-    /// This function will panic if libinput returns an invalid switch state.
+    /// Builds a new switch state from a raw libinput switch state
     pub const fn from_raw(raw: sys::libinput_switch_state) -> Self {
         match raw {
             sys::libinput_switch_state::LIBINPUT_SWITCH_STATE_OFF => Self::Off,
             sys::libinput_switch_state::LIBINPUT_SWITCH_STATE_ON => Self::On,
-            _ => panic!("libinput returned an invalid switch state"), // FIXME: I dont think panicking is a good idea. Maybe we could return an option?
+            sys::libinput_switch_state(raw) => Self::Unknown(raw),
         }
     }
 }
@@ -1,8 +1,11 @@
 //! Events that come from the pad of tablet devices.
 
-use crate::sys::{
-    libinput_event_get_tablet_pad_event, libinput_event_tablet_pad,
-    libinput_event_tablet_pad_get_base_event,
+use crate::{
+    event::{ButtonState, DescribeEvent, EventFields, KeyState, impl_time},
+    sys::{
+        self, libinput_event_get_tablet_pad_event, libinput_event_tablet_pad,
+        libinput_event_tablet_pad_get_base_event,
+    },
 };
 
 // FIXME: better docs
@@ -12,6 +15,7 @@ super::define_events!(
     libinput_event_tablet_pad,
     libinput_event_tablet_pad_get_base_event,
     libinput_event_get_tablet_pad_event,
+    Some(sys::libinput_event_tablet_pad_get_time_usec),
     /// A button pressed on a device
     Button,
     /// A status change on a tablet ring
@@ -23,3 +27,151 @@ super::define_events!(
     /// A status change on a tablet dial
     Dial,
 );
+
+impl_time!(
+    sys::libinput_event_tablet_pad_get_time,
+    sys::libinput_event_tablet_pad_get_time_usec,
+    TabletPadButtonEvent,
+    TabletPadRingEvent,
+    TabletPadStripEvent,
+    TabletPadKeyEvent,
+    TabletPadDialEvent,
+);
+
+/// Generates the mode accessor shared by the events bound to a mode group
+macro_rules! impl_mode {
+    ($($event:ident),+ $(,)?) => {
+        $(
+            impl $event {
+                /// The mode the button, ring or strip that triggered this event is in, at the time of the event
+                pub fn mode(&self) -> u32 {
+                    unsafe { sys::libinput_event_tablet_pad_get_mode(self.raw) }
+                }
+            }
+        )+
+    };
+}
+
+impl_mode!(
+    TabletPadButtonEvent,
+    TabletPadRingEvent,
+    TabletPadStripEvent
+);
+
+impl TabletPadButtonEvent {
+    /// The button number that triggered this event, starting at 0
+    pub fn button_number(&self) -> u32 {
+        unsafe { sys::libinput_event_tablet_pad_get_button_number(self.raw) }
+    }
+
+    /// The button state of the event
+    pub fn button_state(&self) -> ButtonState {
+        ButtonState::from_raw(unsafe { sys::libinput_event_tablet_pad_get_button_state(self.raw) })
+    }
+}
+
+impl TabletPadRingEvent {
+    /// The number of the ring that has changed state, with 0 being the first ring
+    pub fn ring_number(&self) -> u32 {
+        unsafe { sys::libinput_event_tablet_pad_get_ring_number(self.raw) }
+    }
+
+    /// The current position of the ring in degrees counterclockwise from the northern-most point,
+    /// or -1 if the finger was lifted
+    pub fn position(&self) -> f64 {
+        unsafe { sys::libinput_event_tablet_pad_get_ring_position(self.raw) }
+    }
+
+    /// The source of the interaction with the ring
+    pub fn source(&self) -> PadAxisSource {
+        match unsafe { sys::libinput_event_tablet_pad_get_ring_source(self.raw) } {
+            sys::libinput_tablet_pad_ring_axis_source::LIBINPUT_TABLET_PAD_RING_SOURCE_FINGER => {
+                PadAxisSource::Finger
+            }
+            _ => PadAxisSource::Unknown,
+        }
+    }
+}
+
+impl TabletPadStripEvent {
+    /// The number of the strip that has changed state, with 0 being the first strip
+    pub fn strip_number(&self) -> u32 {
+        unsafe { sys::libinput_event_tablet_pad_get_strip_number(self.raw) }
+    }
+
+    /// The current position of the strip normalized to the range [0, 1], or -1 if the finger was lifted
+    pub fn position(&self) -> f64 {
+        unsafe { sys::libinput_event_tablet_pad_get_strip_position(self.raw) }
+    }
+
+    /// The source of the interaction with the strip
+    pub fn source(&self) -> PadAxisSource {
+        match unsafe { sys::libinput_event_tablet_pad_get_strip_source(self.raw) } {
+            sys::libinput_tablet_pad_strip_axis_source::LIBINPUT_TABLET_PAD_STRIP_SOURCE_FINGER => {
+                PadAxisSource::Finger
+            }
+            _ => PadAxisSource::Unknown,
+        }
+    }
+}
+
+impl TabletPadKeyEvent {
+    /// The key code that triggered this event, see `linux/input-event-codes.h`
+    pub fn key(&self) -> u32 {
+        unsafe { sys::libinput_event_tablet_pad_get_key(self.raw) }
+    }
+
+    /// The key state of the event
+    pub fn key_state(&self) -> KeyState {
+        KeyState::from_raw(unsafe { sys::libinput_event_tablet_pad_get_key_state(self.raw) })
+    }
+}
+
+impl DescribeEvent for TabletPadButtonEvent {
+    fn describe(&self, fields: &mut EventFields<'_, '_>) {
+        fields
+            .field("button", &self.button_number())
+            .field("state", &self.button_state())
+            .field("mode", &self.mode());
+    }
+}
+
+impl DescribeEvent for TabletPadRingEvent {
+    fn describe(&self, fields: &mut EventFields<'_, '_>) {
+        fields
+            .field("ring", &self.ring_number())
+            .field("position", &self.position())
+            .field("source", &self.source())
+            .field("mode", &self.mode());
+    }
+}
+
+impl DescribeEvent for TabletPadStripEvent {
+    fn describe(&self, fields: &mut EventFields<'_, '_>) {
+        fields
+            .field("strip", &self.strip_number())
+            .field("position", &self.position())
+            .field("source", &self.source())
+            .field("mode", &self.mode());
+    }
+}
+
+impl DescribeEvent for TabletPadKeyEvent {
+    fn describe(&self, fields: &mut EventFields<'_, '_>) {
+        fields
+            .field("key", &self.key())
+            .field("state", &self.key_state());
+    }
+}
+
+impl DescribeEvent for TabletPadDialEvent {}
+
+/// The source of an interaction with a ring or strip on a tablet pad
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PadAxisSource {
+    /// An unknown source
+    Unknown,
+    /// The event is caused by the movement of one or more fingers
+    Finger,
+}
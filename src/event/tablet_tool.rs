@@ -1,8 +1,11 @@
 //! Events that come from tools on tablet devices.
 
-use crate::sys::{
-    libinput_event_get_tablet_tool_event, libinput_event_tablet_tool,
-    libinput_event_tablet_tool_get_base_event,
+use crate::{
+    event::{ButtonState, DescribeEvent, EventFields, impl_time},
+    sys::{
+        self, libinput_event_get_tablet_tool_event, libinput_event_tablet_tool,
+        libinput_event_tablet_tool_get_base_event,
+    },
 };
 
 // FIXME: better docs
@@ -12,6 +15,7 @@ super::define_events!(
     libinput_event_tablet_tool,
     libinput_event_tablet_tool_get_base_event,
     libinput_event_get_tablet_tool_event,
+    Some(sys::libinput_event_tablet_tool_get_time_usec),
     /// One or more axes have changed state on a device
     Axis,
     /// Signals that a tool has come in or out of proximity of a device
@@ -21,3 +25,362 @@ super::define_events!(
     /// Signals that a tool has changed a logical button state on a device
     Button,
 );
+
+impl_time!(
+    sys::libinput_event_tablet_tool_get_time,
+    sys::libinput_event_tablet_tool_get_time_usec,
+    TabletToolAxisEvent,
+    TabletToolProximityEvent,
+    TabletToolTipEvent,
+    TabletToolButtonEvent,
+);
+
+/// Generates the axis accessors shared by every tablet tool event
+macro_rules! impl_axes {
+    ($($event:ident),+ $(,)?) => {
+        $(
+            impl $event {
+                /// The type of the tool that generated this event
+                pub fn tool_type(&self) -> TabletToolType {
+                    TabletToolType::from_raw(unsafe {
+                        sys::libinput_tablet_tool_get_type(self.tool())
+                    })
+                }
+
+                /// The serial number of the tool, 0 if the tool does not report a serial number
+                pub(crate) fn tool_serial(&self) -> u64 {
+                    unsafe { sys::libinput_tablet_tool_get_serial(self.tool()) }
+                }
+
+                /// The hardware tool id of the tool, 0 if the tool does not report an id
+                pub(crate) fn tool_id(&self) -> u64 {
+                    unsafe { sys::libinput_tablet_tool_get_tool_id(self.tool()) }
+                }
+
+                fn tool(&self) -> *mut sys::libinput_tablet_tool {
+                    unsafe { sys::libinput_event_tablet_tool_get_tool(self.raw) }
+                }
+
+                /// Check if the x axis was updated in this event
+                pub(crate) fn x_has_changed(&self) -> bool {
+                    unsafe { sys::libinput_event_tablet_tool_x_has_changed(self.raw) != 0 }
+                }
+
+                /// Check if the y axis was updated in this event
+                pub(crate) fn y_has_changed(&self) -> bool {
+                    unsafe { sys::libinput_event_tablet_tool_y_has_changed(self.raw) != 0 }
+                }
+
+                /// Check if the pressure axis was updated in this event
+                pub fn pressure_has_changed(&self) -> bool {
+                    unsafe { sys::libinput_event_tablet_tool_pressure_has_changed(self.raw) != 0 }
+                }
+
+                /// Check if the distance axis was updated in this event
+                pub fn distance_has_changed(&self) -> bool {
+                    unsafe { sys::libinput_event_tablet_tool_distance_has_changed(self.raw) != 0 }
+                }
+
+                /// Check if the tilt x axis was updated in this event
+                pub fn tilt_x_has_changed(&self) -> bool {
+                    unsafe { sys::libinput_event_tablet_tool_tilt_x_has_changed(self.raw) != 0 }
+                }
+
+                /// Check if the tilt y axis was updated in this event
+                pub fn tilt_y_has_changed(&self) -> bool {
+                    unsafe { sys::libinput_event_tablet_tool_tilt_y_has_changed(self.raw) != 0 }
+                }
+
+                /// Check if the z-rotation axis was updated in this event
+                pub fn rotation_has_changed(&self) -> bool {
+                    unsafe { sys::libinput_event_tablet_tool_rotation_has_changed(self.raw) != 0 }
+                }
+
+                /// Check if the slider axis was updated in this event
+                pub(crate) fn slider_has_changed(&self) -> bool {
+                    unsafe { sys::libinput_event_tablet_tool_slider_has_changed(self.raw) != 0 }
+                }
+
+                /// Check if the size major axis was updated in this event
+                pub(crate) fn size_major_has_changed(&self) -> bool {
+                    unsafe { sys::libinput_event_tablet_tool_size_major_has_changed(self.raw) != 0 }
+                }
+
+                /// Check if the size minor axis was updated in this event
+                pub(crate) fn size_minor_has_changed(&self) -> bool {
+                    unsafe { sys::libinput_event_tablet_tool_size_minor_has_changed(self.raw) != 0 }
+                }
+
+                /// Check if the wheel axis was updated in this event
+                pub fn wheel_has_changed(&self) -> bool {
+                    unsafe { sys::libinput_event_tablet_tool_wheel_has_changed(self.raw) != 0 }
+                }
+
+                /// The x coordinate of the tool in mm from the top left corner of the tablet in its current logical orientation
+                pub fn x(&self) -> f64 {
+                    unsafe { sys::libinput_event_tablet_tool_get_x(self.raw) }
+                }
+
+                /// The y coordinate of the tool in mm from the top left corner of the tablet in its current logical orientation
+                pub fn y(&self) -> f64 {
+                    unsafe { sys::libinput_event_tablet_tool_get_y(self.raw) }
+                }
+
+                /// The delta between the last event and the current event on the x axis, in mm
+                pub(crate) fn dx(&self) -> f64 {
+                    unsafe { sys::libinput_event_tablet_tool_get_dx(self.raw) }
+                }
+
+                /// The delta between the last event and the current event on the y axis, in mm
+                pub(crate) fn dy(&self) -> f64 {
+                    unsafe { sys::libinput_event_tablet_tool_get_dy(self.raw) }
+                }
+
+                /// The current pressure being applied on the tool, normalized to the range [0, 1]
+                pub fn pressure(&self) -> f64 {
+                    unsafe { sys::libinput_event_tablet_tool_get_pressure(self.raw) }
+                }
+
+                /// The current distance from the tablet's sensor, normalized to the range [0, 1]
+                pub fn distance(&self) -> f64 {
+                    unsafe { sys::libinput_event_tablet_tool_get_distance(self.raw) }
+                }
+
+                /// The current tilt along the x axis of the tablet's logical orientation, in degrees off the tablet's z axis
+                pub fn tilt_x(&self) -> f64 {
+                    unsafe { sys::libinput_event_tablet_tool_get_tilt_x(self.raw) }
+                }
+
+                /// The current tilt along the y axis of the tablet's logical orientation, in degrees off the tablet's z axis
+                pub fn tilt_y(&self) -> f64 {
+                    unsafe { sys::libinput_event_tablet_tool_get_tilt_y(self.raw) }
+                }
+
+                /// The current z rotation of the tool in degrees, clockwise from the tool's logical neutral position
+                pub fn rotation(&self) -> f64 {
+                    unsafe { sys::libinput_event_tablet_tool_get_rotation(self.raw) }
+                }
+
+                /// The current position of the slider on the tool, normalized to the range [-1, 1]
+                pub(crate) fn slider_position(&self) -> f64 {
+                    unsafe { sys::libinput_event_tablet_tool_get_slider_position(self.raw) }
+                }
+
+                /// The current size in mm along the major axis of the touching ellipse
+                pub(crate) fn size_major(&self) -> f64 {
+                    unsafe { sys::libinput_event_tablet_tool_get_size_major(self.raw) }
+                }
+
+                /// The current size in mm along the minor axis of the touching ellipse
+                pub(crate) fn size_minor(&self) -> f64 {
+                    unsafe { sys::libinput_event_tablet_tool_get_size_minor(self.raw) }
+                }
+
+                /// The delta for the wheel in degrees
+                pub fn wheel_delta(&self) -> f64 {
+                    unsafe { sys::libinput_event_tablet_tool_get_wheel_delta(self.raw) }
+                }
+
+                /// The delta for the wheel in discrete steps
+                pub(crate) fn wheel_delta_discrete(&self) -> i32 {
+                    unsafe { sys::libinput_event_tablet_tool_get_wheel_delta_discrete(self.raw) }
+                }
+
+
+
+                fn describe_axes(&self, fields: &mut EventFields<'_, '_>) {
+                    fields
+                        .field("tool", &self.tool_type())
+                        .field("x", &self.x())
+                        .field("y", &self.y());
+
+                    if self.pressure_has_changed() {
+                        fields.field("pressure", &self.pressure());
+                    }
+
+                    if self.distance_has_changed() {
+                        fields.field("distance", &self.distance());
+                    }
+
+                    if self.tilt_x_has_changed() || self.tilt_y_has_changed() {
+                        fields
+                            .field("tilt_x", &self.tilt_x())
+                            .field("tilt_y", &self.tilt_y());
+                    }
+
+                    if self.rotation_has_changed() {
+                        fields.field("rotation", &self.rotation());
+                    }
+
+                    if self.wheel_has_changed() {
+                        fields.field("wheel", &self.wheel_delta());
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_axes!(
+    TabletToolAxisEvent,
+    TabletToolProximityEvent,
+    TabletToolTipEvent,
+    TabletToolButtonEvent,
+);
+
+impl TabletToolProximityEvent {
+    /// Returns the new proximity state of a tool
+    pub fn proximity_state(&self) -> ProximityState {
+        ProximityState::from_raw(unsafe {
+            sys::libinput_event_tablet_tool_get_proximity_state(self.raw)
+        })
+    }
+}
+
+impl TabletToolTipEvent {
+    /// Returns the new tip state of a tool
+    pub fn tip_state(&self) -> TipState {
+        TipState::from_raw(unsafe { sys::libinput_event_tablet_tool_get_tip_state(self.raw) })
+    }
+}
+
+impl TabletToolButtonEvent {
+    /// The button that triggered this event, see `linux/input-event-codes.h`
+    pub fn button(&self) -> u32 {
+        unsafe { sys::libinput_event_tablet_tool_get_button(self.raw) }
+    }
+
+    /// The button state of the event
+    pub fn button_state(&self) -> ButtonState {
+        ButtonState::from_raw(unsafe { sys::libinput_event_tablet_tool_get_button_state(self.raw) })
+    }
+
+    /// The total number of buttons pressed on all devices on the associated seat after the event was triggered
+    pub fn seat_button_count(&self) -> u32 {
+        unsafe { sys::libinput_event_tablet_tool_get_seat_button_count(self.raw) }
+    }
+}
+
+impl DescribeEvent for TabletToolAxisEvent {
+    fn describe(&self, fields: &mut EventFields<'_, '_>) {
+        self.describe_axes(fields);
+    }
+}
+
+impl DescribeEvent for TabletToolProximityEvent {
+    fn describe(&self, fields: &mut EventFields<'_, '_>) {
+        fields.field("state", &self.proximity_state());
+        self.describe_axes(fields);
+    }
+}
+
+impl DescribeEvent for TabletToolTipEvent {
+    fn describe(&self, fields: &mut EventFields<'_, '_>) {
+        fields.field("state", &self.tip_state());
+        self.describe_axes(fields);
+    }
+}
+
+impl DescribeEvent for TabletToolButtonEvent {
+    fn describe(&self, fields: &mut EventFields<'_, '_>) {
+        fields
+            .field("button", &self.button())
+            .field("state", &self.button_state());
+        self.describe_axes(fields);
+    }
+}
+
+/// Available tool types for a device with the tablet tool capability.
+/// The tool type defines the default usage of the tool as advertised by the manufacturer.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum TabletToolType {
+    /// A generic pen
+    Pen,
+    /// Eraser
+    Eraser,
+    /// A paintbrush-like tool
+    Brush,
+    /// Physical drawing tool, e.g. Wacom Inking Pen
+    Pencil,
+    /// An airbrush-like tool
+    Airbrush,
+    /// A mouse bound to the tablet
+    Mouse,
+    /// A mouse tool with a lens
+    Lens,
+    /// A rotary device with positional and rotation data
+    Totem,
+    /// A tool type not known to this version of colpetto
+    Unknown(u32),
+}
+
+impl TabletToolType {
+    /// Builds a new tool type from a raw libinput one
+    pub const fn from_raw(raw: sys::libinput_tablet_tool_type) -> Self {
+        match raw {
+            sys::libinput_tablet_tool_type::LIBINPUT_TABLET_TOOL_TYPE_PEN => Self::Pen,
+            sys::libinput_tablet_tool_type::LIBINPUT_TABLET_TOOL_TYPE_ERASER => Self::Eraser,
+            sys::libinput_tablet_tool_type::LIBINPUT_TABLET_TOOL_TYPE_BRUSH => Self::Brush,
+            sys::libinput_tablet_tool_type::LIBINPUT_TABLET_TOOL_TYPE_PENCIL => Self::Pencil,
+            sys::libinput_tablet_tool_type::LIBINPUT_TABLET_TOOL_TYPE_AIRBRUSH => Self::Airbrush,
+            sys::libinput_tablet_tool_type::LIBINPUT_TABLET_TOOL_TYPE_MOUSE => Self::Mouse,
+            sys::libinput_tablet_tool_type::LIBINPUT_TABLET_TOOL_TYPE_LENS => Self::Lens,
+            sys::libinput_tablet_tool_type::LIBINPUT_TABLET_TOOL_TYPE_TOTEM => Self::Totem,
+            sys::libinput_tablet_tool_type(raw) => Self::Unknown(raw),
+        }
+    }
+}
+
+/// The state of proximity for a tool on a device
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum ProximityState {
+    /// The tool left the detectable distance of the tablet
+    Out,
+    /// The tool entered the detectable distance of the tablet
+    In,
+    /// A proximity state not known to this version of colpetto
+    Unknown(u32),
+}
+
+impl ProximityState {
+    /// Builds a new proximity state from a raw libinput one
+    pub const fn from_raw(raw: sys::libinput_tablet_tool_proximity_state) -> Self {
+        match raw {
+            sys::libinput_tablet_tool_proximity_state::LIBINPUT_TABLET_TOOL_PROXIMITY_STATE_OUT => {
+                Self::Out
+            }
+            sys::libinput_tablet_tool_proximity_state::LIBINPUT_TABLET_TOOL_PROXIMITY_STATE_IN => {
+                Self::In
+            }
+            sys::libinput_tablet_tool_proximity_state(raw) => Self::Unknown(raw),
+        }
+    }
+}
+
+/// The tip contact state for a tool on a device
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum TipState {
+    /// The tool stopped touching the surface
+    Up,
+    /// The tool is in contact with the surface
+    Down,
+    /// A tip state not known to this version of colpetto
+    Unknown(u32),
+}
+
+impl TipState {
+    /// Builds a new tip state from a raw libinput one
+    pub const fn from_raw(raw: sys::libinput_tablet_tool_tip_state) -> Self {
+        match raw {
+            sys::libinput_tablet_tool_tip_state::LIBINPUT_TABLET_TOOL_TIP_UP => Self::Up,
+            sys::libinput_tablet_tool_tip_state::LIBINPUT_TABLET_TOOL_TIP_DOWN => Self::Down,
+            sys::libinput_tablet_tool_tip_state(raw) => Self::Unknown(raw),
+        }
+    }
+}
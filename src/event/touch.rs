@@ -2,8 +2,12 @@
 
 //! Events from absolute touch devices.
 
-use crate::sys::{
-    libinput_event_get_touch_event, libinput_event_touch, libinput_event_touch_get_base_event,
+use crate::{
+    event::{DescribeEvent, EventFields, impl_time},
+    sys::{
+        self, libinput_event_get_touch_event, libinput_event_touch,
+        libinput_event_touch_get_base_event,
+    },
 };
 
 super::define_events!(
@@ -11,9 +15,97 @@ super::define_events!(
     libinput_event_touch,
     libinput_event_touch_get_base_event,
     libinput_event_get_touch_event,
+    Some(sys::libinput_event_touch_get_time_usec),
     Down,
     Up,
     Motion,
     Cancel,
     Frame,
 );
+
+impl_time!(
+    sys::libinput_event_touch_get_time,
+    sys::libinput_event_touch_get_time_usec,
+    TouchDownEvent,
+    TouchUpEvent,
+    TouchMotionEvent,
+    TouchCancelEvent,
+    TouchFrameEvent,
+);
+
+/// Generates the slot accessors shared by the touch point events
+macro_rules! impl_slot {
+    ($($event:ident),+ $(,)?) => {
+        $(
+            impl $event {
+                /// The slot of this touch event, unique within the device. May be -1 on single touch devices.
+                pub fn slot(&self) -> i32 {
+                    unsafe { sys::libinput_event_touch_get_slot(self.raw) }
+                }
+
+                /// The seat slot of this touch event, unique within the seat.
+                pub fn seat_slot(&self) -> i32 {
+                    unsafe { sys::libinput_event_touch_get_seat_slot(self.raw) }
+                }
+            }
+        )+
+    };
+}
+
+impl_slot!(
+    TouchDownEvent,
+    TouchUpEvent,
+    TouchMotionEvent,
+    TouchCancelEvent
+);
+
+/// Generates the coordinate accessors shared by the down and motion events
+macro_rules! impl_position {
+    ($($event:ident),+ $(,)?) => {
+        $(
+            impl $event {
+                /// The current absolute x coordinate of the touch event, in mm from the top left corner of the device.
+                pub fn x(&self) -> f64 {
+                    unsafe { sys::libinput_event_touch_get_x(self.raw) }
+                }
+
+                /// The current absolute y coordinate of the touch event, in mm from the top left corner of the device.
+                pub fn y(&self) -> f64 {
+                    unsafe { sys::libinput_event_touch_get_y(self.raw) }
+                }
+
+
+            }
+
+            impl DescribeEvent for $event {
+                fn describe(&self, fields: &mut EventFields<'_, '_>) {
+                    fields
+                        .field("slot", &self.slot())
+                        .field("seat_slot", &self.seat_slot())
+                        .field("x", &self.x())
+                        .field("y", &self.y());
+                }
+            }
+        )+
+    };
+}
+
+impl_position!(TouchDownEvent, TouchMotionEvent);
+
+impl DescribeEvent for TouchUpEvent {
+    fn describe(&self, fields: &mut EventFields<'_, '_>) {
+        fields
+            .field("slot", &self.slot())
+            .field("seat_slot", &self.seat_slot());
+    }
+}
+
+impl DescribeEvent for TouchCancelEvent {
+    fn describe(&self, fields: &mut EventFields<'_, '_>) {
+        fields
+            .field("slot", &self.slot())
+            .field("seat_slot", &self.seat_slot());
+    }
+}
+
+impl DescribeEvent for TouchFrameEvent {}
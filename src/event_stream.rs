@@ -4,7 +4,7 @@ use std::{
     task::{self, Poll},
 };
 
-use futures_core::{Stream, ready};
+use futures_core::Stream;
 use tokio::io::unix::AsyncFd;
 
 use crate::{Error, Event, Libinput, Result};
@@ -47,19 +47,22 @@ use crate::{Error, Event, Libinput, Result};
 ///
 /// The stream internally manages an [`AsyncFd`] wrapper around the libinput file descriptor,
 /// ensuring efficient integration with tokio's event loop. It will only wake up when new
-/// events are available to be read from the libinput context.
+/// events are available to be read from the libinput context, or when the directory watched with
+/// [`Libinput::watch_devices`] changes.
 #[derive(Debug)]
 pub struct EventStream {
     libinput: Libinput,
     fd: AsyncFd<RawFd>,
+    watch_fd: Option<AsyncFd<RawFd>>,
     is_first: bool,
 }
 
 impl EventStream {
-    pub(crate) fn new(libinput: Libinput, fd: RawFd) -> Result<Self> {
+    pub(crate) fn new(libinput: Libinput, fd: RawFd, watch_fd: Option<RawFd>) -> Result<Self> {
         Ok(Self {
             libinput,
             fd: AsyncFd::new(fd)?,
+            watch_fd: watch_fd.map(AsyncFd::new).transpose()?,
             is_first: true,
         })
     }
@@ -82,13 +85,32 @@ impl Stream for EventStream {
                 }
             }
 
-            let mut guard = ready!(self.fd.poll_read_ready(cx))?;
+            let guard = match self.fd.poll_read_ready(cx) {
+                Poll::Ready(guard) => Some(guard?),
+                Poll::Pending => None,
+            };
+
+            let watch_guard = match &self.watch_fd {
+                Some(fd) => match fd.poll_read_ready(cx) {
+                    Poll::Ready(guard) => Some(guard?),
+                    Poll::Pending => None,
+                },
+                None => None,
+            };
+
+            if guard.is_none() && watch_guard.is_none() {
+                return Poll::Pending;
+            }
+
+            // Dispatching also applies the changes of the watched directory
             self.libinput.dispatch()?;
 
             if let Some(event) = self.libinput.get_event() {
                 return Poll::Ready(Some(Ok(event)));
             } else {
-                guard.clear_ready();
+                for mut guard in guard.into_iter().chain(watch_guard) {
+                    guard.clear_ready();
+                }
                 continue;
             }
         }
@@ -0,0 +1,418 @@
+//! A privilege-separated device opener.
+//!
+//! The [`Server`] runs in a small privileged process and opens devices on behalf of an unprivileged
+//! [`Client`], handing the fds over a Unix socket with `SCM_RIGHTS`. Every requested path is checked
+//! against a [`Policy`] first, by default only `/dev/input/event*` devices are handed out.
+//!
+//! The client implements [`OpenInterface`], so it can be passed to [`Libinput::with_interface`](crate::Libinput::with_interface) directly.
+//!
+//! # Example usage
+//!
+//! ```
+//! use std::os::unix::net::UnixStream;
+//!
+//! use colpetto::{
+//!     Libinput,
+//!     fd_broker::{Client, Policy, Server},
+//! };
+//!
+//! let (server, client) = UnixStream::pair()?;
+//!
+//! // Usually done in a separate privileged process
+//! std::thread::spawn(move || Server::new(server, Policy::new()).run());
+//!
+//! let libinput = Libinput::with_interface(Client::new(client), None)?;
+//! ```
+//!
+//! # Protocol
+//!
+//! A request is the open flags and the length of the path as native endian `u32`s, followed by the path.
+//! The reply is a native endian `i32`, `0` on success with the fd attached, or the errno of the failure.
+
+use std::{
+    io::{self, Read, Write},
+    mem::MaybeUninit,
+    os::{
+        fd::{AsFd, OwnedFd},
+        unix::{
+            ffi::{OsStrExt, OsStringExt},
+            net::UnixStream,
+        },
+    },
+    path::{Path, PathBuf},
+};
+
+use rustix::{
+    fs::Mode,
+    io::Errno,
+    net::{
+        RecvAncillaryBuffer, RecvAncillaryMessage, RecvFlags, SendAncillaryBuffer,
+        SendAncillaryMessage, SendFlags,
+    },
+};
+
+use crate::{OFlags, OpenInterface};
+
+/// Paths longer than this are rejected without being read
+const MAX_PATH_LEN: u32 = 4096;
+
+/// The only flags a client may request, anything else is stripped by the server
+const ALLOWED_FLAGS: OFlags = OFlags::RDONLY
+    .union(OFlags::WRONLY)
+    .union(OFlags::RDWR)
+    .union(OFlags::NONBLOCK);
+
+/// Decides which paths the [`Server`] opens.
+///
+/// Paths are resolved before being checked, so symlinks cannot be used to escape the policy.
+/// Allowed are the `/dev/input/event*` devices, unless disabled with [`without_event_devices`](Self::without_event_devices),
+/// and the paths in the allowlist.
+#[derive(Debug, Clone)]
+pub struct Policy {
+    event_devices: bool,
+    allowlist: Vec<PathBuf>,
+}
+
+impl Policy {
+    /// Creates a policy allowing only the `/dev/input/event*` devices
+    pub fn new() -> Self {
+        Self {
+            event_devices: true,
+            allowlist: Vec::new(),
+        }
+    }
+
+    /// Disallows the `/dev/input/event*` devices, leaving only the allowlist
+    pub fn without_event_devices(mut self) -> Self {
+        self.event_devices = false;
+        self
+    }
+
+    /// Adds `path` to the allowlist. Relative paths and symlinks are resolved when the server checks a request,
+    /// so the allowlist should contain canonical paths.
+    pub fn allow(mut self, path: impl Into<PathBuf>) -> Self {
+        self.allowlist.push(path.into());
+        self
+    }
+
+    /// Returns `true` if the resolved `path` may be opened
+    pub fn is_allowed(&self, path: &Path) -> bool {
+        (self.event_devices && is_event_device(path))
+            || self.allowlist.iter().any(|allowed| allowed == path)
+    }
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn is_event_device(path: &Path) -> bool {
+    path.parent() == Some(Path::new("/dev/input"))
+        && path
+            .file_name()
+            .and_then(|name| name.as_bytes().strip_prefix(b"event"))
+            .is_some_and(|number| !number.is_empty() && number.iter().all(u8::is_ascii_digit))
+}
+
+/// The privileged side of the broker, serving the requests of a single client
+#[derive(Debug)]
+pub struct Server {
+    socket: UnixStream,
+    policy: Policy,
+}
+
+impl Server {
+    /// Creates a server answering the requests received on `socket`
+    pub fn new(socket: UnixStream, policy: Policy) -> Self {
+        Self { socket, policy }
+    }
+
+    /// Serves requests until the client disconnects
+    pub fn run(mut self) -> io::Result<()> {
+        while self.serve_one()? {}
+
+        Ok(())
+    }
+
+    /// Serves a single request, returning `false` if the client disconnected.
+    ///
+    /// Denied and failed opens are reported to the client and are not errors,
+    /// only failures of the socket itself are returned.
+    pub fn serve_one(&mut self) -> io::Result<bool> {
+        let mut header = [0; 8];
+
+        match self.socket.read_exact(&mut header) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(false),
+            Err(err) => return Err(err),
+        }
+
+        let [f0, f1, f2, f3, l0, l1, l2, l3] = header;
+        let flags = OFlags::from_bits_retain(u32::from_ne_bytes([f0, f1, f2, f3]));
+        let len = u32::from_ne_bytes([l0, l1, l2, l3]);
+
+        if len > MAX_PATH_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("requested path is {len} bytes long"),
+            ));
+        }
+
+        let mut path = vec![0; len as usize];
+        self.socket.read_exact(&mut path)?;
+        let path = PathBuf::from(std::ffi::OsString::from_vec(path));
+
+        match self.open(&path, flags) {
+            Ok(fd) => self.reply(0, Some(fd))?,
+            Err(errno) => self.reply(errno.raw_os_error(), None)?,
+        }
+
+        Ok(true)
+    }
+
+    fn open(&self, path: &Path, flags: OFlags) -> Result<OwnedFd, Errno> {
+        let path = path
+            .canonicalize()
+            .map_err(|err| Errno::from_io_error(&err).unwrap_or(Errno::ACCESS))?;
+
+        if !self.policy.is_allowed(&path) {
+            return Err(Errno::ACCESS);
+        }
+
+        rustix::fs::open(
+            &path,
+            (flags & ALLOWED_FLAGS) | OFlags::CLOEXEC | OFlags::NOFOLLOW,
+            Mode::empty(),
+        )
+    }
+
+    fn reply(&self, status: i32, fd: Option<OwnedFd>) -> io::Result<()> {
+        let fds = fd.as_ref().map(AsFd::as_fd);
+        let fds = fds.as_slice();
+
+        let mut space = [MaybeUninit::uninit(); rustix::cmsg_space!(ScmRights(1))];
+        let mut control = SendAncillaryBuffer::new(&mut space);
+
+        if !fds.is_empty() {
+            control.push(SendAncillaryMessage::ScmRights(fds));
+        }
+
+        let status = status.to_ne_bytes();
+        let sent = rustix::net::sendmsg(
+            &self.socket,
+            &[io::IoSlice::new(&status)],
+            &mut control,
+            SendFlags::NOSIGNAL,
+        )?;
+
+        // The fd travels with the first byte, the rest of the reply can be sent as usual
+        (&self.socket).write_all(&status[sent..])
+    }
+}
+
+/// The unprivileged side of the broker, opening devices through a [`Server`]
+#[derive(Debug)]
+pub struct Client {
+    socket: UnixStream,
+}
+
+impl Client {
+    /// Creates a client sending its requests on `socket`
+    pub fn new(socket: UnixStream) -> Self {
+        Self { socket }
+    }
+
+    /// Consumes the client, returning the underlying socket
+    pub fn into_inner(self) -> UnixStream {
+        self.socket
+    }
+}
+
+impl OpenInterface for Client {
+    fn open(&mut self, path: &Path, flags: OFlags) -> io::Result<OwnedFd> {
+        let path = path.as_os_str().as_bytes();
+        let len = u32::try_from(path.len())
+            .ok()
+            .filter(|&len| len <= MAX_PATH_LEN)
+            .ok_or_else(|| io::Error::from_raw_os_error(Errno::NAMETOOLONG.raw_os_error()))?;
+
+        let mut request = Vec::with_capacity(8 + path.len());
+        request.extend_from_slice(&flags.bits().to_ne_bytes());
+        request.extend_from_slice(&len.to_ne_bytes());
+        request.extend_from_slice(path);
+
+        self.socket.write_all(&request)?;
+
+        let mut status = [0; 4];
+        let mut space = [MaybeUninit::uninit(); rustix::cmsg_space!(ScmRights(1))];
+        let mut control = RecvAncillaryBuffer::new(&mut space);
+
+        let received = rustix::net::recvmsg(
+            &self.socket,
+            &mut [io::IoSliceMut::new(&mut status)],
+            &mut control,
+            RecvFlags::CMSG_CLOEXEC,
+        )?
+        .bytes;
+
+        if received == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+
+        let fd = control.drain().find_map(|message| match message {
+            RecvAncillaryMessage::ScmRights(mut fds) => fds.next(),
+            _ => None,
+        });
+
+        self.socket.read_exact(&mut status[received..])?;
+
+        match (i32::from_ne_bytes(status), fd) {
+            (0, Some(fd)) => Ok(fd),
+            (0, None) => Err(io::Error::other("the broker replied without an fd")),
+            (errno, _) => Err(io::Error::from_raw_os_error(errno)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, thread};
+
+    use super::*;
+
+    /// A file in the temporary directory, removed on drop
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str, contents: &str) -> Self {
+            let path = std::env::temp_dir()
+                .join(format!("colpetto-fd-broker-{}-{name}", std::process::id()));
+            std::fs::write(&path, contents).unwrap();
+
+            Self(path.canonicalize().unwrap())
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    fn serve(policy: Policy) -> (Client, thread::JoinHandle<io::Result<()>>) {
+        let (server, client) = UnixStream::pair().unwrap();
+        let server = thread::spawn(move || Server::new(server, policy).run());
+
+        (Client::new(client), server)
+    }
+
+    fn request(flags: u32, len: u32, path: &[u8]) -> Vec<u8> {
+        let mut request = Vec::new();
+        request.extend_from_slice(&flags.to_ne_bytes());
+        request.extend_from_slice(&len.to_ne_bytes());
+        request.extend_from_slice(path);
+        request
+    }
+
+    #[test]
+    fn allowed_path_returns_usable_fd() {
+        let file = TempFile::new("allowed", "hello");
+        let (mut client, server) = serve(Policy::new().allow(&file.0));
+
+        let fd = client.open(&file.0, OFlags::RDONLY).unwrap();
+        let mut contents = String::new();
+        File::from(fd).read_to_string(&mut contents).unwrap();
+        assert_eq!(contents, "hello");
+
+        drop(client);
+        server.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn rejected_path_returns_eacces() {
+        let allowed = TempFile::new("policy-allowed", "");
+        let denied = TempFile::new("policy-denied", "");
+        let (mut client, server) = serve(Policy::new().without_event_devices().allow(&allowed.0));
+
+        let error = client.open(&denied.0, OFlags::RDONLY).unwrap_err();
+        assert_eq!(error.raw_os_error(), Some(Errno::ACCESS.raw_os_error()));
+
+        // The server keeps serving after a denial
+        assert!(client.open(&allowed.0, OFlags::RDONLY).is_ok());
+
+        drop(client);
+        server.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn missing_path_is_reported() {
+        let (mut client, server) = serve(Policy::new());
+
+        let error = client
+            .open(
+                Path::new("/dev/input/event-colpetto-missing"),
+                OFlags::RDONLY,
+            )
+            .unwrap_err();
+        assert_eq!(error.raw_os_error(), Some(Errno::NOENT.raw_os_error()));
+
+        drop(client);
+        server.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn truncated_request_is_an_error() {
+        let (server, mut client) = UnixStream::pair().unwrap();
+
+        client.write_all(&request(0, 16, b"/dev")).unwrap();
+        drop(client);
+
+        let error = Server::new(server, Policy::new()).serve_one().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn oversized_request_is_rejected_unread() {
+        let (server, mut client) = UnixStream::pair().unwrap();
+
+        client
+            .write_all(&request(0, MAX_PATH_LEN + 1, b""))
+            .unwrap();
+
+        let error = Server::new(server, Policy::new()).serve_one().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn client_refuses_oversized_path() {
+        let (_server, client) = UnixStream::pair().unwrap();
+        let path = PathBuf::from("/".repeat(MAX_PATH_LEN as usize + 1));
+
+        let error = Client::new(client).open(&path, OFlags::RDONLY).unwrap_err();
+        assert_eq!(
+            error.raw_os_error(),
+            Some(Errno::NAMETOOLONG.raw_os_error())
+        );
+    }
+
+    #[test]
+    fn disconnect_stops_the_server() {
+        let (server, client) = UnixStream::pair().unwrap();
+        drop(client);
+
+        assert!(!Server::new(server, Policy::new()).serve_one().unwrap());
+    }
+
+    #[test]
+    fn event_devices() {
+        assert!(is_event_device(Path::new("/dev/input/event0")));
+        assert!(is_event_device(Path::new("/dev/input/event12")));
+        assert!(!is_event_device(Path::new("/dev/input/event")));
+        assert!(!is_event_device(Path::new("/dev/input/event1a")));
+        assert!(!is_event_device(Path::new("/dev/input/mouse0")));
+        assert!(!is_event_device(Path::new("/dev/input/by-id/event0")));
+        assert!(!is_event_device(Path::new("/tmp/event0")));
+    }
+}
@@ -0,0 +1,310 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::BTreeMap,
+    ffi::{CStr, c_int},
+    io,
+    os::{
+        fd::{BorrowedFd, FromRawFd, IntoRawFd, OwnedFd, RawFd},
+        unix::ffi::OsStrExt,
+    },
+    path::{Path, PathBuf},
+};
+
+pub use rustix::fs::OFlags;
+use rustix::ioctl::{IntegerSetter, Opcode, ioctl, opcode};
+
+use crate::{AccessRequest, Handler, LogPriority, LogRecord};
+
+/// The interface libinput uses to open and close the devices it manages.
+///
+/// Since libinput usually runs without the privileges needed to access `/dev/input`,
+/// opening devices is delegated to the caller, e.g. to a session manager such as logind or seatd.
+/// Methods take `&mut self` so implementations can keep state, like the set of devices they handed out.
+///
+/// A pair of closures `(open, close)` also implements this trait.
+///
+/// # Example usage
+///
+/// ```
+/// use std::{io, os::fd::OwnedFd, path::Path};
+///
+/// use colpetto::{Libinput, OFlags, OpenInterface};
+/// use rustix::fs::{Mode, open};
+///
+/// struct Interface;
+///
+/// impl OpenInterface for Interface {
+///     fn open(&mut self, path: &Path, flags: OFlags) -> io::Result<OwnedFd> {
+///         Ok(open(path, flags, Mode::empty())?)
+///     }
+/// }
+///
+/// let libinput = Libinput::with_interface(Interface, None)?;
+/// ```
+pub trait OpenInterface {
+    /// Opens the device at `path` with the given flags
+    fn open(&mut self, path: &Path, flags: OFlags) -> io::Result<OwnedFd>;
+
+    /// Closes a file descriptor previously returned by [`open`](Self::open).
+    /// The default implementation simply drops it.
+    fn close(&mut self, fd: OwnedFd) {
+        drop(fd);
+    }
+}
+
+impl<O, C> OpenInterface for (O, C)
+where
+    O: FnMut(&Path, OFlags) -> io::Result<OwnedFd>,
+    C: FnMut(OwnedFd),
+{
+    fn open(&mut self, path: &Path, flags: OFlags) -> io::Result<OwnedFd> {
+        (self.0)(path, flags)
+    }
+
+    fn close(&mut self, fd: OwnedFd) {
+        (self.1)(fd)
+    }
+}
+
+/// Adapts the raw closures taken by [`Libinput::new`](crate::Libinput::new) and friends to [`OpenInterface`]
+pub(crate) struct RawInterface<O, C> {
+    pub(crate) open: O,
+    pub(crate) close: C,
+}
+
+impl<O, C> OpenInterface for RawInterface<O, C>
+where
+    O: Fn(&CStr, c_int) -> Result<RawFd, c_int>,
+    C: Fn(c_int),
+{
+    fn open(&mut self, path: &Path, flags: OFlags) -> io::Result<OwnedFd> {
+        let mut bytes = path.as_os_str().as_bytes().to_vec();
+        bytes.push(0);
+
+        let path = CStr::from_bytes_until_nul(&bytes).map_err(io::Error::other)?;
+
+        match (self.open)(path, flags.bits() as c_int) {
+            // Safety: the closure hands over ownership of the fd
+            Ok(fd) if fd >= 0 => Ok(unsafe { OwnedFd::from_raw_fd(fd) }),
+            // Both positive and negated errnos are accepted, on either side of the result
+            Ok(errno) | Err(errno) => Err(io::Error::from_raw_os_error(errno.saturating_abs())),
+        }
+    }
+
+    fn close(&mut self, fd: OwnedFd) {
+        (self.close)(fd.into_raw_fd())
+    }
+}
+
+/// Turns an error of the open interface into the negated errno libinput expects.
+/// Errors without a valid errno are reported as `EIO`, so they can never be mistaken for a file descriptor.
+pub(crate) fn negated_errno(error: &io::Error) -> c_int {
+    let errno = error
+        .raw_os_error()
+        .map(c_int::saturating_abs)
+        .filter(|errno| *errno > 0)
+        .unwrap_or(rustix::io::Errno::IO.raw_os_error());
+
+    -errno
+}
+
+/// A file descriptor handed to libinput by the open interface and not closed yet,
+/// see [`Libinput::open_fds`](crate::Libinput::open_fds)
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct OpenFd {
+    /// The file descriptor
+    pub fd: RawFd,
+    /// The path it was opened from
+    pub path: PathBuf,
+    /// The flags it was opened with
+    pub flags: OFlags,
+}
+
+impl OpenFd {
+    /// The sysname of the device, e.g. `event5` for `/dev/input/event5`
+    fn sysname(&self) -> Option<String> {
+        sysname(&self.path)
+    }
+}
+
+fn sysname(path: &Path) -> Option<String> {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+}
+
+/// `EVIOCREVOKE`, from `linux/input.h`
+const EVIOCREVOKE: Opcode = opcode::write::<c_int>(b'E', 0x91);
+
+/// Keeps track of the fds currently owned by libinput
+#[derive(Default)]
+pub(crate) struct FdTracker {
+    fds: RefCell<BTreeMap<RawFd, OpenFd>>,
+    /// Whether the fds are revoked when the context is suspended
+    pub(crate) revoke_on_suspend: Cell<bool>,
+}
+
+impl FdTracker {
+    pub(crate) fn list(&self) -> Vec<OpenFd> {
+        self.fds.borrow().values().cloned().collect()
+    }
+}
+
+impl Handler {
+    /// Opens a device through the interface, tracking the returned fd
+    pub(crate) fn open_fd(&self, path: &Path, flags: OFlags) -> io::Result<RawFd> {
+        if let Some(policy) = &*self.policy.borrow() {
+            let request = AccessRequest::from_udev(&self.udev, path);
+
+            if let Err(denied) = policy.check(&request) {
+                self.log(&LogRecord::colpetto(
+                    LogPriority::Info,
+                    sysname(path),
+                    format!("not opening {}, {denied}", path.display()),
+                ));
+
+                return Err(io::Error::from_raw_os_error(
+                    rustix::io::Errno::ACCESS.raw_os_error(),
+                ));
+            }
+        }
+
+        let fd = self.interface.borrow_mut().open(path, flags)?.into_raw_fd();
+
+        let open_fd = OpenFd {
+            fd,
+            path: path.to_owned(),
+            flags,
+        };
+
+        if let Some(previous) = self.fds.fds.borrow_mut().insert(fd, open_fd) {
+            self.log(&LogRecord::colpetto(
+                LogPriority::Error,
+                previous.sysname(),
+                format!(
+                    "open returned fd {fd} for {}, but it is still open for {}",
+                    path.display(),
+                    previous.path.display()
+                ),
+            ));
+        }
+
+        Ok(fd)
+    }
+
+    /// Closes an fd through the interface, ignoring fds that were not opened by this context
+    pub(crate) fn close_fd(&self, fd: RawFd) {
+        if self.fds.fds.borrow_mut().remove(&fd).is_none() {
+            self.log(&LogRecord::colpetto(
+                LogPriority::Error,
+                None,
+                format!(
+                    "close called on fd {fd}, which is not open through this context, ignoring"
+                ),
+            ));
+
+            return;
+        }
+
+        // Safety: the fd was returned by the interface and not closed since
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        self.interface.borrow_mut().close(fd);
+    }
+
+    /// Revokes access to every tracked fd with `EVIOCREVOKE`, making any copy of them unusable.
+    /// Revoking a file that is not an evdev device fails and is only logged.
+    pub(crate) fn revoke_fds(&self) {
+        for open_fd in self.fds.fds.borrow().values() {
+            // Safety: the fd was returned by the interface and not closed since
+            let fd = unsafe { BorrowedFd::borrow_raw(open_fd.fd) };
+
+            // Safety: EVIOCREVOKE takes an integer argument, which must be 0
+            let result = unsafe { ioctl(fd, IntegerSetter::<EVIOCREVOKE>::new_usize(0)) };
+
+            if let Err(errno) = result {
+                self.log(&LogRecord::colpetto(
+                    LogPriority::Error,
+                    open_fd.sysname(),
+                    format!(
+                        "failed to revoke fd {} for {}: {errno}",
+                        open_fd.fd,
+                        open_fd.path.display()
+                    ),
+                ));
+            }
+        }
+    }
+
+    /// Reports and closes the fds libinput did not close before being destroyed
+    pub(crate) fn close_leaked_fds(&self) {
+        let fds = std::mem::take(&mut *self.fds.fds.borrow_mut());
+
+        for open_fd in fds.into_values() {
+            self.log(&LogRecord::colpetto(
+                LogPriority::Error,
+                open_fd.sysname(),
+                format!(
+                    "fd {} for {} was not closed by libinput, closing it",
+                    open_fd.fd,
+                    open_fd.path.display()
+                ),
+            ));
+
+            // Safety: the fd was returned by the interface and not closed since
+            let fd = unsafe { OwnedFd::from_raw_fd(open_fd.fd) };
+
+            self.interface.borrow_mut().close(fd);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::fd::AsRawFd;
+
+    use super::*;
+
+    const EIO: c_int = rustix::io::Errno::IO.raw_os_error();
+    const EACCES: c_int = rustix::io::Errno::ACCESS.raw_os_error();
+
+    fn raw_open(result: Result<RawFd, c_int>) -> io::Result<OwnedFd> {
+        let mut interface = RawInterface {
+            open: move |_: &CStr, _| result,
+            close: |_| {},
+        };
+
+        interface.open(Path::new("/dev/input/event0"), OFlags::RDWR)
+    }
+
+    #[test]
+    fn raw_open_accepts_either_errno_sign() {
+        for result in [Ok(-EACCES), Err(-EACCES), Err(EACCES)] {
+            let error = raw_open(result).expect_err("negative fds are errors");
+            assert_eq!(error.raw_os_error(), Some(EACCES), "{result:?}");
+        }
+
+        let error = raw_open(Ok(-1)).expect_err("negative fds are errors");
+        assert_eq!(error.raw_os_error(), Some(1));
+    }
+
+    #[test]
+    fn raw_open_takes_ownership_of_the_fd() {
+        let file = std::fs::File::open("/dev/null").unwrap();
+        let fd = file.into_raw_fd();
+
+        let owned = raw_open(Ok(fd)).unwrap();
+        assert_eq!(owned.as_raw_fd(), fd);
+    }
+
+    #[test]
+    fn negated_errno_is_always_negative() {
+        let errno = |errno| negated_errno(&io::Error::from_raw_os_error(errno));
+
+        assert_eq!(errno(EACCES), -EACCES);
+        assert_eq!(errno(-EACCES), -EACCES);
+        assert_eq!(errno(0), -EIO);
+        assert_eq!(errno(c_int::MIN), -c_int::MAX);
+        assert_eq!(negated_errno(&io::Error::other("no errno")), -EIO);
+    }
+}
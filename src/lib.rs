@@ -19,32 +19,79 @@
 pub mod sys;
 
 use std::{
-    ffi::{CStr, c_char, c_int, c_void},
-    io, mem,
-    os::fd::RawFd,
+    any::Any,
+    cell::{Cell, RefCell},
+    ffi::{CStr, CString, OsStr, c_char, c_int, c_void},
+    io,
+    mem::{self, ManuallyDrop},
+    os::{fd::RawFd, unix::ffi::OsStrExt},
+    panic::{self, AssertUnwindSafe},
+    path::Path,
     ptr::NonNull,
-    rc::Rc,
+    rc::{Rc, Weak},
+    sync::Mutex,
 };
 
 use devil::Udev;
 
+use crate::{
+    diagnostics::History,
+    event::{AsRawEvent, DeviceEvent},
+    interface::{FdTracker, RawInterface},
+    path_watcher::PathWatcher,
+    tracker::DeviceTracker,
+};
+
+mod access;
+mod capabilities;
+mod config;
 mod device;
 mod device_group;
+mod device_id;
+mod diagnostics;
+mod interface;
 mod logger;
+mod path_watcher;
+mod properties;
+mod registry;
 mod seat;
+mod tracker;
+mod udev;
 
+pub mod backend;
 pub mod event;
-
+#[cfg(feature = "fd-broker")]
+pub mod fd_broker;
+#[cfg(feature = "record")]
+pub mod record;
+#[cfg(feature = "serde")]
+pub mod schema;
+pub mod session;
+#[cfg(feature = "thread")]
+pub mod thread;
+
+pub use access::{Access, AccessDenied, AccessPolicy, AccessRequest, DeviceMatch};
+pub use capabilities::*;
+pub use config::*;
 pub use device::*;
 pub use device_group::*;
-pub use event::Event;
-pub use logger::Logger;
+pub use device_id::*;
+pub use diagnostics::{DeviceDiagnostics, Diagnostics, OpenFailure};
+pub use event::{DeviceInfo, Event, OwnedEvent};
+pub use interface::{OFlags, OpenFd, OpenInterface};
+pub use logger::{LogCategory, LogPriority, LogRecord, Logger};
+pub use properties::*;
+pub use registry::*;
 pub use seat::*;
 
 #[cfg(feature = "tokio")]
 mod event_stream;
 #[cfg(feature = "tokio")]
 pub use event_stream::EventStream;
+#[cfg(feature = "tokio")]
+mod seat_manager;
+#[cfg(feature = "tokio")]
+pub use seat_manager::{SeatEvent, SeatManager};
 
 /// Generic error type for libinput
 #[allow(missing_docs)]
@@ -56,8 +103,48 @@ pub enum Error {
     Context,
     #[error("Failed to assign seat")]
     Seat,
+    #[error("Failed to add device")]
+    AddDevice,
+    #[error("Seat {0:?} is already managed")]
+    DuplicateSeat(CString),
+    #[error("Unknown seat {0:?}")]
+    UnknownSeat(CString),
     #[error("{0}")]
     IoError(#[from] io::Error),
+    #[error("{0}")]
+    CallbackPanicked(#[from] CallbackPanic),
+}
+
+/// A panic caught in a callback invoked by libinput, returned by [`Libinput::dispatch`] as [`Error::CallbackPanicked`].
+///
+/// Unwinding through libinput is undefined behaviour, so panics in the open interface and the logger
+/// are caught at the boundary. Opens fail with `EIO`, closes and log messages are dropped.
+#[derive(Debug, thiserror::Error)]
+#[error("the {callback} callback panicked: {}", self.message().unwrap_or("Box<dyn Any>"))]
+pub struct CallbackPanic {
+    callback: &'static str,
+    message: Option<String>,
+    // Behind a mutex so the error stays `Sync`
+    payload: Mutex<Box<dyn Any + Send>>,
+}
+
+impl CallbackPanic {
+    /// The callback that panicked, e.g. `open`, `close` or `log`
+    pub fn callback(&self) -> &'static str {
+        self.callback
+    }
+
+    /// The panic message, if the payload is a string
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    /// Returns the panic payload, e.g. to resume unwinding with [`std::panic::resume_unwind`]
+    pub fn into_payload(self) -> Box<dyn Any + Send> {
+        self.payload
+            .into_inner()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
 }
 
 /// Convient alias for colpetto errors
@@ -72,10 +159,25 @@ unsafe extern "C" fn open_restricted(
     let handler = unsafe { &*handler }; // Safe because we manage the user_data pointer
 
     // Safety is guaranteed by libinput
-    match (handler.open)(unsafe { CStr::from_ptr(path) }, flags) {
+    let path = Path::new(OsStr::from_bytes(
+        unsafe { CStr::from_ptr(path) }.to_bytes(),
+    ));
+    let flags = OFlags::from_bits_retain(flags as u32);
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| handler.open_fd(path, flags)))
+        .unwrap_or_else(|payload| {
+            handler.store_panic("open", payload);
+            Err(io::Error::other("the open callback panicked"))
+        });
+
+    match result {
         Ok(fd) => fd,
-        Err(errno) if errno.is_positive() => errno.wrapping_neg(),
-        Err(errno) => errno,
+        Err(error) => {
+            let errno = interface::negated_errno(&error);
+            handler.history.record_open_failure(path, flags, error);
+
+            errno
+        }
     }
 }
 
@@ -83,7 +185,9 @@ unsafe extern "C" fn close_restricted(fd: c_int, user_data: *mut c_void) {
     let handler = user_data as *const Handler;
     let handler = unsafe { &*handler };
 
-    (handler.close)(fd)
+    if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| handler.close_fd(fd))) {
+        handler.store_panic("close", payload);
+    }
 }
 
 const INTERFACE: sys::libinput_interface = sys::libinput_interface {
@@ -97,80 +201,191 @@ pub struct Libinput {
     raw: NonNull<sys::libinput>,
 }
 
-#[allow(clippy::type_complexity)] // No point in making a type alias no one will use nor see
+/// The device discovery backend of a context
+#[derive(Clone, Copy)]
+enum Backend {
+    Udev,
+    Path,
+}
+
 struct Handler {
-    open: Box<dyn Fn(&CStr, c_int) -> Result<RawFd, c_int> + 'static>,
-    close: Box<dyn Fn(c_int) + 'static>,
+    interface: RefCell<Box<dyn OpenInterface>>,
+    fds: FdTracker,
+    id: u64,
+    logger: Option<Logger>,
+    // Shared with the pending `Removed` futures, which must not outlive the handler
+    devices: Rc<DeviceTracker>,
+    history: History,
+    panic: RefCell<Option<CallbackPanic>>,
+    policy: RefCell<Option<AccessPolicy>>,
+    udev: Udev,
+    watcher: RefCell<Option<PathWatcher>>,
+    /// Whether the context is suspended, so that a watcher created meanwhile does not add devices
+    suspended: Cell<bool>,
+}
+
+impl Handler {
+    /// Stores a panic caught in a callback, to be returned by the next dispatch.
+    /// Only the first panic is kept.
+    pub(crate) fn store_panic(&self, callback: &'static str, payload: Box<dyn Any + Send>) {
+        let mut panic = self.panic.borrow_mut();
+
+        if panic.is_none() {
+            let message = payload
+                .downcast_ref::<&'static str>()
+                .map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned());
+
+            *panic = Some(CallbackPanic {
+                callback,
+                message,
+                payload: Mutex::new(payload),
+            });
+        }
+    }
+
+    /// Retrieves the handler stored in the user data of a context
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure the context was created by colpetto and is still alive
+    pub(crate) unsafe fn from_context<'a>(libinput: *mut sys::libinput) -> &'a Self {
+        // Safe because we manage the user_data pointer
+        unsafe { &*(sys::libinput_get_user_data(libinput) as *const Handler) }
+    }
+
+    /// Returns a weak handle to the handler stored in the user data of a context
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure the context was created by colpetto and is still alive
+    pub(crate) unsafe fn downgrade(libinput: *mut sys::libinput) -> Weak<Self> {
+        // The user data owns one strong reference, which must not be released here
+        let handler = unsafe {
+            ManuallyDrop::new(Rc::from_raw(
+                sys::libinput_get_user_data(libinput) as *const Handler
+            ))
+        };
+
+        Rc::downgrade(&handler)
+    }
 }
 
 impl Libinput {
-    /// Creates a new libinput context. For more information see [`with_logger`](Self::with_logger).
+    /// Creates a new libinput context without a logger. For more information see [`with_logger`](Self::with_logger).
     pub fn new<O, C>(open: O, close: C) -> Result<Self>
     where
         O: Fn(&CStr, c_int) -> Result<RawFd, c_int> + 'static,
         C: Fn(c_int) + 'static,
     {
-        Self::with_logger(open, close, None)
+        Self::with_interface(RawInterface { open, close }, None)
     }
 
-    /// Creates a new libinput context with tracing pre-configured. For more information see [`with_logger`](Self::with_logger).
+    /// Creates a new libinput context with tracing pre-configured, see [`Logger::tracing`].
+    /// For more information see [`with_logger`](Self::with_logger).
     #[cfg(feature = "tracing")]
     pub fn with_tracing<O, C>(open: O, close: C) -> Result<Self>
     where
         O: Fn(&CStr, c_int) -> Result<RawFd, c_int> + 'static,
         C: Fn(c_int) + 'static,
     {
-        unsafe extern "C" fn tracing_logger(
-            priority: sys::libinput_log_priority,
-            message: *const c_char,
-        ) {
-            use tracing::{debug, error, info, trace};
-
-            // Safety guaranted by libinput
-            let message = unsafe { CStr::from_ptr(message) }.to_string_lossy();
-            let message = message.trim_ascii_end();
-
-            match priority {
-                sys::libinput_log_priority::LIBINPUT_LOG_PRIORITY_INFO => info!("{message}"),
-                sys::libinput_log_priority::LIBINPUT_LOG_PRIORITY_DEBUG => debug!("{message}"),
-                sys::libinput_log_priority::LIBINPUT_LOG_PRIORITY_ERROR => error!("{message}"),
-                _ => trace!("{message}"),
-            }
-        }
-
-        Self::with_logger(open, close, Some(tracing_logger))
+        Self::with_interface(RawInterface { open, close }, Some(Logger::tracing()))
     }
 
     /// Creates a new libinput context with the given logger.
     ///
+    /// The `open` callback receives the path and the raw `open(2)` flags and returns either a file descriptor
+    /// or an errno, which may be positive or negated. The `close` callback receives the file descriptors returned by `open`.
+    /// These are thin adapters over [`OpenInterface`], see [`with_interface`](Self::with_interface).
+    ///
     /// Internally this will create a new libudev instance and create the internal context with it.
     ///
+    /// Every context owns its logger, which receives the messages of that context only, with the trailing newline stripped.
+    /// Use [`Logger::structured`] with [`with_interface`](Self::with_interface) to receive them parsed into a [`LogRecord`] instead.
+    /// The log priority starts at [`LogPriority::Debug`] so that filtering can be left to the logger,
+    /// use [`set_log_priority`](Self::set_log_priority) to silence libinput at the source.
+    ///
     /// This function will return an error if either udev or libinput fail to create a context.
-    pub fn with_logger<O, C>(open: O, close: C, logger: Logger) -> Result<Self>
+    pub fn with_logger<O, C, L>(open: O, close: C, logger: L) -> Result<Self>
+    where
+        O: Fn(&CStr, c_int) -> Result<RawFd, c_int> + 'static,
+        C: Fn(c_int) + 'static,
+        L: Fn(LogPriority, &str) + 'static,
+    {
+        Self::with_interface(RawInterface { open, close }, Some(Logger::new(logger)))
+    }
+
+    /// Creates a new libinput context forwarding its messages to the [`log`](https://docs.rs/log) crate, see [`Logger::log`].
+    /// For more information see [`with_logger`](Self::with_logger).
+    #[cfg(feature = "log")]
+    pub fn with_log<O, C>(open: O, close: C) -> Result<Self>
     where
         O: Fn(&CStr, c_int) -> Result<RawFd, c_int> + 'static,
         C: Fn(c_int) + 'static,
+    {
+        Self::with_interface(RawInterface { open, close }, Some(Logger::log()))
+    }
+
+    /// Creates a new libinput context opening devices through `interface`, with an optional logger.
+    /// Without a logger, libinput errors are printed to stderr.
+    ///
+    /// For more information see [`with_logger`](Self::with_logger).
+    pub fn with_interface<I>(interface: I, logger: Option<Logger>) -> Result<Self>
+    where
+        I: OpenInterface + 'static,
+    {
+        Self::create(interface, logger, Backend::Udev)
+    }
+
+    /// Creates a new path context opening devices through `interface`, with an optional logger.
+    ///
+    /// Path contexts do not use udev to discover devices, they are added by hand with
+    /// [`path_add_device`](Self::path_add_device) or by watching a directory with [`watch_devices`](Self::watch_devices).
+    pub fn with_path_interface<I>(interface: I, logger: Option<Logger>) -> Result<Self>
+    where
+        I: OpenInterface + 'static,
+    {
+        Self::create(interface, logger, Backend::Path)
+    }
+
+    fn create<I>(interface: I, logger: Option<Logger>, backend: Backend) -> Result<Self>
+    where
+        I: OpenInterface + 'static,
     {
         let udev = Udev::new()?;
 
+        let has_logger = logger.is_some();
+
         let handler = Rc::new(Handler {
-            open: Box::new(open),
-            close: Box::new(close),
+            interface: RefCell::new(Box::new(interface)),
+            fds: FdTracker::default(),
+            id: logger::next_context_id(),
+            logger,
+            devices: Rc::default(),
+            history: History::default(),
+            panic: RefCell::new(None),
+            policy: RefCell::new(None),
+            udev: udev.clone(),
+            watcher: RefCell::new(None),
+            suspended: Cell::new(false),
         });
 
+        let user_data = Rc::into_raw(handler) as *const _ as _;
+
         let libinput = unsafe {
-            sys::libinput_udev_create_context(
-                &INTERFACE,
-                Rc::into_raw(handler) as *const _ as _,
-                udev.as_raw().cast(),
-            )
+            match backend {
+                Backend::Udev => {
+                    sys::libinput_udev_create_context(&INTERFACE, user_data, udev.as_raw().cast())
+                }
+                Backend::Path => sys::libinput_path_create_context(&INTERFACE, user_data),
+            }
         };
 
         if libinput.is_null() {
             return Err(Error::Context);
         }
 
-        logger::setup_logger(libinput, logger);
+        logger::setup_logger(libinput, has_logger);
 
         Ok(Self {
             raw: unsafe { NonNull::new_unchecked(libinput) },
@@ -182,6 +397,71 @@ impl Libinput {
         self.raw.as_ptr()
     }
 
+    fn handler(&self) -> &Handler {
+        unsafe { Handler::from_context(self.as_raw()) }
+    }
+
+    /// Returns an id identifying this context, unique within the process and shared by its clones
+    pub fn context_id(&self) -> u64 {
+        self.handler().id
+    }
+
+    /// Captures a report of the state of this context, to attach to bug reports.
+    ///
+    /// The report lists the devices known to the context along with their ids, capabilities, configuration and
+    /// relevant udev properties, the last log records and the last devices that failed to open.
+    /// Only messages at or above the [`log_priority`](Self::log_priority) are recorded.
+    pub fn diagnostics(&self) -> Diagnostics {
+        let handler = self.handler();
+
+        Diagnostics::new(
+            handler.id,
+            self.log_priority(),
+            &handler.devices.devices(),
+            &handler.history,
+        )
+    }
+
+    /// Returns the most recent devices that the `open` callback failed to open, oldest first.
+    ///
+    /// libinput ignores devices it cannot open, so this is the place to look when a device produces no events,
+    /// e.g. because `/dev/input/event7` returned `EACCES`. Only the last few failures are kept.
+    pub fn open_failures(&self) -> Vec<OpenFailure> {
+        self.handler().history.open_failures()
+    }
+
+    /// Returns the file descriptors currently handed to libinput by the open interface, sorted by fd.
+    ///
+    /// Every fd returned by the interface is tracked until libinput closes it. Close calls for unknown fds
+    /// are reported through the logger and ignored, and fds still open once the last handle to the context
+    /// is dropped are reported and closed.
+    pub fn open_fds(&self) -> Vec<OpenFd> {
+        self.handler().fds.list()
+    }
+
+    /// Forgets the failures returned by [`open_failures`](Self::open_failures)
+    pub fn clear_open_failures(&self) {
+        self.handler().history.clear_open_failures();
+    }
+
+    /// Sets the policy deciding which devices are passed to the open interface, `None` allows every device.
+    ///
+    /// The policy applies to devices opened after this call, so it should be set before
+    /// [`udev_assign_seat`](Self::udev_assign_seat). See [`AccessPolicy`] for details.
+    pub fn set_access_policy(&self, policy: Option<AccessPolicy>) {
+        *self.handler().policy.borrow_mut() = policy;
+    }
+
+    /// Sets the minimum priority of the messages forwarded to the logger of this context
+    pub fn set_log_priority(&self, priority: LogPriority) {
+        unsafe { sys::libinput_log_set_priority(self.as_raw(), priority.as_raw()) }
+    }
+
+    /// Returns the minimum priority of the messages forwarded to the logger of this context
+    pub fn log_priority(&self) -> LogPriority {
+        LogPriority::from_raw(unsafe { sys::libinput_log_get_priority(self.as_raw()) })
+    }
+
     /// libinput keeps a single file descriptor for all events, [`dispatch`](Self::dispatch) should be called only when events are avaiable on this fd
     pub fn get_fd(&self) -> i32 {
         unsafe { sys::libinput_get_fd(self.as_raw()) }
@@ -192,27 +472,73 @@ impl Libinput {
     ///
     /// Dispatching does not necessarily queue libinput events. This function should be called immediately once data is available on the file descriptor returned by [`get_fd`](Self::get_fd).
     /// libinput has a number of timing-sensitive features (e.g. tap-to-click), any delay in calling [`dispatch`](Self::dispatch) may prevent these features from working correctly.
+    ///
+    /// # Errors
+    ///
+    /// If a callback such as the open interface or the logger panicked since the last dispatch, the panic is
+    /// caught before it can unwind through libinput and returned here as [`Error::CallbackPanicked`].
     pub fn dispatch(&self) -> Result<(), Error> {
-        unsafe {
-            match sys::libinput_dispatch(self.as_raw()) {
-                0 => Ok(()),
-                e => Err(Error::IoError(io::Error::from_raw_os_error(-e))),
-            }
+        if let Some(watcher) = &mut *self.handler().watcher.borrow_mut() {
+            watcher.dispatch(self.as_raw())?;
+        }
+
+        let result = unsafe { sys::libinput_dispatch(self.as_raw()) };
+
+        if let Some(panic) = self.handler().panic.take() {
+            return Err(Error::CallbackPanicked(panic));
+        }
+
+        match result {
+            0 => Ok(()),
+            e => Err(Error::IoError(io::Error::from_raw_os_error(-e))),
         }
     }
 
     /// Suspend monitoring for new devices and close existing devices.
     /// This all but terminates libinput but does keep the context valid to be resumed with [`resume`](Self::resume).
+    ///
+    /// If enabled with [`set_revoke_on_suspend`](Self::set_revoke_on_suspend), devices are revoked before being closed.
     pub fn suspend(&self) {
+        let handler = self.handler();
+
+        if handler.fds.revoke_on_suspend.get() {
+            handler.revoke_fds();
+        }
+
+        // Watched devices are added back by the rescan on resume, not by libinput.
+        // Nodes appearing meanwhile are ignored, adding them would reopen devices on a suspended context.
+        if let Some(watcher) = &mut *handler.watcher.borrow_mut() {
+            watcher.suspend();
+        }
+
+        handler.suspended.set(true);
+
         unsafe { sys::libinput_suspend(self.as_raw()) }
     }
 
+    /// Enables or disables revoking devices on [`suspend`](Self::suspend), disabled by default.
+    ///
+    /// Closing an fd does not stop copies of it from reading input, e.g. one kept by the open interface,
+    /// a session manager or a forked child. When enabled, every fd handed to libinput is revoked with
+    /// `EVIOCREVOKE` before being closed, so a backgrounded compositor cannot keep snooping on input after
+    /// its session became inactive. Devices are reopened through the open interface on [`resume`](Self::resume).
+    pub fn set_revoke_on_suspend(&self, enabled: bool) {
+        self.handler().fds.revoke_on_suspend.set(enabled);
+    }
+
     /// Resume a suspended libinput context. This re-enables device monitoring and adds existing devices
     pub fn resume(&self) -> Result<(), Error> {
-        match unsafe { sys::libinput_resume(self.as_raw()) } {
-            0 => Ok(()),
-            _ => Err(Error::Resume),
+        if unsafe { sys::libinput_resume(self.as_raw()) } != 0 {
+            return Err(Error::Resume);
+        }
+
+        self.handler().suspended.set(false);
+
+        if let Some(watcher) = &mut *self.handler().watcher.borrow_mut() {
+            watcher.resume(self.as_raw())?;
         }
+
+        Ok(())
     }
 
     /// Retrieve the next event from libinput's internal event queue.
@@ -229,7 +555,19 @@ impl Libinput {
             return None;
         }
 
-        Some(unsafe { Event::from_raw(event, event_type) })
+        let event = unsafe { Event::from_raw(event, event_type) };
+
+        match &event {
+            Event::Device(DeviceEvent::Added(e)) => {
+                self.handler().devices.device_added(&e.device())
+            }
+            Event::Device(DeviceEvent::Removed(e)) => {
+                self.handler().devices.device_removed(&e.device())
+            }
+            _ => {}
+        }
+
+        Some(event)
     }
 
     /// Assigns a seat to this libinput context. After assignment, device changes (additions or removals)
@@ -237,11 +575,11 @@ impl Libinput {
     ///
     /// This function succeeds even when:
     /// - No input devices are currently available on the specified seat
-    /// - Available devices fail to open via [`OpenCallback`]
+    /// - Available devices fail to open via the `open` callback
     ///
     /// Devices that lack minimum capabilities to function as a pointer, keyboard, or touch device
     /// are ignored until the next call to [`resume()`](Self::resume). The same applies to
-    /// devices that failed to open.
+    /// devices that failed to open, which are recorded in [`open_failures`](Self::open_failures).
     ///
     /// # Errors
     /// This function may only be called once per context. Subsequent calls will result in an error.
@@ -251,6 +589,71 @@ impl Libinput {
             _ => Err(Error::Seat),
         }
     }
+
+    /// Adds the device at `path` to a path context, see [`with_path_interface`](Self::with_path_interface).
+    /// The device is re-opened on [`resume`](Self::resume) until removed with [`path_remove_device`](Self::path_remove_device).
+    ///
+    /// # Errors
+    /// Fails if the device cannot be opened or is not an input device libinput can handle,
+    /// and always on udev contexts.
+    pub fn path_add_device(&self, path: &Path) -> Result<Device, Error> {
+        let path = CString::new(path.as_os_str().as_bytes()).map_err(|_| Error::AddDevice)?;
+
+        let raw = unsafe { sys::libinput_path_add_device(self.as_raw(), path.as_ptr()) };
+
+        if raw.is_null() {
+            return Err(Error::AddDevice);
+        }
+
+        Ok(unsafe { Device::from_raw(raw) })
+    }
+
+    /// Removes a device from a path context. Devices not in this context are ignored.
+    pub fn path_remove_device(&self, device: &Device) {
+        unsafe { sys::libinput_path_remove_device(device.as_raw()) }
+    }
+
+    /// Watches `dir`, usually `/dev/input`, with inotify, adding the `event*` nodes already present
+    /// and hotplugging the ones appearing or disappearing later. This provides hotplug on path contexts
+    /// where udev is not running, e.g. in containers.
+    ///
+    /// New nodes are picked up by [`dispatch`](Self::dispatch), which must be called when [`watch_fd`](Self::watch_fd)
+    /// becomes readable. The tokio `EventStream` takes care of this if it is created after this call.
+    /// Calling this again replaces the previous watch, removing the devices it added.
+    /// While the context is suspended nodes are not added until [`resume`](Self::resume).
+    /// The inotify fd is reused, so [`watch_fd`](Self::watch_fd) and existing event streams stay valid.
+    pub fn watch_devices(&self, dir: impl AsRef<Path>) -> Result<(), Error> {
+        let mut current = self.handler().watcher.borrow_mut();
+
+        let watcher = match &mut *current {
+            Some(watcher) => {
+                watcher.watch(dir.as_ref())?;
+                watcher
+            }
+            None => {
+                let watcher = current.insert(PathWatcher::new(dir.as_ref())?);
+
+                if self.handler().suspended.get() {
+                    watcher.suspend();
+                }
+
+                watcher
+            }
+        };
+
+        watcher.rescan(self.as_raw())?;
+
+        Ok(())
+    }
+
+    /// Returns the inotify fd of the directory watched with [`watch_devices`](Self::watch_devices), if any
+    pub fn watch_fd(&self) -> Option<RawFd> {
+        self.handler()
+            .watcher
+            .borrow()
+            .as_ref()
+            .map(PathWatcher::fd)
+    }
 }
 
 impl Drop for Libinput {
@@ -258,8 +661,23 @@ impl Drop for Libinput {
         let user_data = unsafe { sys::libinput_get_user_data(self.as_raw()) };
 
         unsafe {
+            let handler = Rc::<Handler>::from_raw(user_data.cast());
+
+            let last = Rc::strong_count(&handler) == 1;
+
+            // Tracked devices must be released while the context is still alive
+            if last {
+                handler.devices.clear();
+                handler.watcher.take();
+            }
+
             sys::libinput_unref(self.as_raw());
-            drop(Rc::<Handler>::from_raw(user_data.cast()));
+
+            if last {
+                handler.close_leaked_fds();
+            }
+
+            drop(handler);
         }
     }
 }
@@ -293,26 +711,28 @@ impl Libinput {
     ///
     /// Panics if called outside of a tokio context
     pub fn event_stream(&self) -> Result<EventStream, Error> {
-        EventStream::new(self.clone(), self.get_fd())
+        EventStream::new(self.clone(), self.get_fd(), self.watch_fd())
     }
 }
 
-mod macros {
-    /// Implements `std::fmt::Debug` on each provided type,
-    /// writing `Name(<ptr>)` to obfuscate the pointer field.
-    macro_rules! impl_debug {
-        ($($name:ident),+) => {
-            $(
-                impl std::fmt::Debug for $name {
-                    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                        write!(f, "{}(<ptr>)", stringify!($name))
-                    }
-                }
-            )+
+impl std::fmt::Debug for Libinput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let devices = self.handler().devices.devices();
+        let mut sysnames: Vec<_> = devices.iter().map(Device::sysname).collect();
+        sysnames.sort_unstable();
+
+        let alternate = f.alternate();
+
+        let mut debug = f.debug_struct("Libinput");
+
+        if alternate {
+            debug.field("raw", &self.raw);
         }
-    }
 
-    pub(crate) use impl_debug;
+        debug
+            .field("id", &self.context_id())
+            .field("fd", &self.get_fd())
+            .field("devices", &sysnames)
+            .finish()
+    }
 }
-
-macros::impl_debug!(Libinput);
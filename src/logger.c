@@ -3,21 +3,19 @@
 #include <stdio.h>
 #include <stdlib.h>
 
-typedef void (*LogCallback)(enum libinput_log_priority, const char *);
-
-static LogCallback global_callback = NULL;
+// Implemented in Rust, looks up the logger of the context through its user data
+extern void colpetto_inner_log_message(struct libinput *libinput,
+                                       enum libinput_log_priority priority,
+                                       const char *message);
 
 // This is our main handler function that libinput will call.
 // It receives the variable arguments (va_list) from libinput and converts them
-// into a simple string that we can pass to Rust.
+// into a simple string that we can pass to Rust, along with the context so
+// each context can have its own logger.
 void colpetto_inner_log_handler(struct libinput *libinput,
                                 enum libinput_log_priority priority,
                                 const char *format, va_list args) {
 
-  // If no callback is set, we can't do anything
-  if (!global_callback)
-    return;
-
   // vsnprintf with NULL buffer returns the required size.
   va_list args_copy;
   va_copy(args_copy, args);
@@ -35,15 +33,11 @@ void colpetto_inner_log_handler(struct libinput *libinput,
   // Actually format the string into our buffer
   vsnprintf(buffer, size + 1, format, args);
 
-  global_callback(priority, buffer);
+  colpetto_inner_log_message(libinput, priority, buffer);
 
   free(buffer);
 }
 
-extern void colpetto_inner_set_log_callback(LogCallback callback) {
-  global_callback = callback;
-}
-
 extern libinput_log_handler colpetto_inner_get_log_handler() {
   return colpetto_inner_log_handler;
 }
\ No newline at end of file
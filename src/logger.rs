@@ -1,34 +1,458 @@
-#![allow(clippy::missing_safety_doc)]
+use std::{
+    ffi::{CStr, c_char},
+    panic::{self, AssertUnwindSafe},
+    sync::atomic::{AtomicU64, Ordering},
+};
 
-use std::ffi::c_char;
+use crate::{Handler, sys};
 
-use crate::sys;
+/// The logger of a context, receiving every message of that context.
+///
+/// Built from a closure with [`new`](Self::new) or [`structured`](Self::structured), or from one of the built-in loggers.
+#[allow(clippy::type_complexity)] // No point in making a type alias no one will use nor see
+pub struct Logger {
+    /// Called with the id of the context, the record and the message as formatted by libinput
+    inner: Box<dyn Fn(u64, &LogRecord, &str) + 'static>,
+}
+
+impl Logger {
+    /// Creates a logger calling `logger` with the priority and the text of every message, without the trailing newline
+    pub fn new<L>(logger: L) -> Self
+    where
+        L: Fn(LogPriority, &str) + 'static,
+    {
+        Self {
+            inner: Box::new(move |_, record, message| logger(record.priority, message)),
+        }
+    }
+
+    /// Creates a logger calling `logger` with every message parsed into a [`LogRecord`]
+    pub fn structured<L>(logger: L) -> Self
+    where
+        L: Fn(&LogRecord) + 'static,
+    {
+        Self {
+            inner: Box::new(move |_, record, _| logger(record)),
+        }
+    }
+
+    /// Creates a logger forwarding records to [`tracing`](https://docs.rs/tracing) events.
+    /// The record category and device are attached as the `category` and `device` fields.
+    #[cfg(feature = "tracing")]
+    pub fn tracing() -> Self {
+        Self::structured(|record| {
+            use tracing::{debug, error, info};
+
+            let category = record.category.name();
+            let device = record.device_sysname.as_deref();
+            let message = &record.message;
+
+            match record.priority {
+                LogPriority::Debug => debug!(category, device, "{message}"),
+                LogPriority::Info => info!(category, device, "{message}"),
+                LogPriority::Error => error!(category, device, "{message}"),
+            }
+        })
+    }
+
+    /// Creates a logger forwarding records to the [`log`](https://docs.rs/log) crate.
+    ///
+    /// Records are emitted with the `colpetto::libinput` target and carry the
+    /// [`context_id`](crate::Libinput::context_id) as the `context` key-value field, so messages from multiple
+    /// contexts can be told apart. The record category and device are attached as the `category` and `device` fields.
+    #[cfg(feature = "log")]
+    pub fn log() -> Self {
+        Self {
+            inner: Box::new(|context, record, _| {
+                let level = match record.priority {
+                    LogPriority::Debug => log::Level::Debug,
+                    LogPriority::Info => log::Level::Info,
+                    LogPriority::Error => log::Level::Error,
+                };
+
+                log::log!(
+                    target: "colpetto::libinput",
+                    level,
+                    context,
+                    category = record.category.name(),
+                    device = record.device_sysname.as_deref();
+                    "{}",
+                    record.message
+                );
+            }),
+        }
+    }
+}
+
+impl std::fmt::Debug for Logger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Logger").finish_non_exhaustive()
+    }
+}
+
+/// Priority of a libinput log message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogPriority {
+    /// Debug messages, very verbose
+    Debug,
+    /// Informational messages
+    Info,
+    /// Errors
+    Error,
+}
+
+impl LogPriority {
+    /// Builds a new log priority from a raw libinput one.
+    /// Unknown priorities are rounded up to the next known one.
+    pub const fn from_raw(raw: sys::libinput_log_priority) -> Self {
+        if raw.0 <= sys::libinput_log_priority::LIBINPUT_LOG_PRIORITY_DEBUG.0 {
+            Self::Debug
+        } else if raw.0 <= sys::libinput_log_priority::LIBINPUT_LOG_PRIORITY_INFO.0 {
+            Self::Info
+        } else {
+            Self::Error
+        }
+    }
+
+    /// Returns the raw libinput priority
+    pub const fn as_raw(self) -> sys::libinput_log_priority {
+        match self {
+            Self::Debug => sys::libinput_log_priority::LIBINPUT_LOG_PRIORITY_DEBUG,
+            Self::Info => sys::libinput_log_priority::LIBINPUT_LOG_PRIORITY_INFO,
+            Self::Error => sys::libinput_log_priority::LIBINPUT_LOG_PRIORITY_ERROR,
+        }
+    }
+}
+
+impl std::fmt::Display for LogPriority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Debug => write!(f, "debug"),
+            Self::Info => write!(f, "info"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// The kind of a libinput log message, taken from the tag libinput prefixes it with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LogCategory {
+    /// An untagged message
+    General,
+    /// The caller is misusing libinput, e.g. `client bug: event processing lagging behind`
+    ClientBug,
+    /// The kernel or the device sent data libinput did not expect
+    KernelBug,
+    /// An internal inconsistency in libinput itself
+    LibinputBug,
+    /// A message from the device quirks subsystem
+    Quirks,
+}
+
+impl LogCategory {
+    /// Message prefixes and the category they map to
+    const TAGS: [(&str, Self); 4] = [
+        ("client bug: ", Self::ClientBug),
+        ("kernel bug: ", Self::KernelBug),
+        ("libinput bug: ", Self::LibinputBug),
+        ("quirks: ", Self::Quirks),
+    ];
+
+    /// Returns a short lowercase name for the category, e.g. `kernel-bug`
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::General => "general",
+            Self::ClientBug => "client-bug",
+            Self::KernelBug => "kernel-bug",
+            Self::LibinputBug => "libinput-bug",
+            Self::Quirks => "quirks",
+        }
+    }
+}
+
+impl std::fmt::Display for LogCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A libinput log message, split into its structured parts.
+///
+/// libinput prefixes messages about a device with its padded sysname (`event5  - `),
+/// followed for non-debug messages by the device name, and tags bug reports with a category
+/// such as `kernel bug: `. These prefixes are stripped from [`message`](Self::message).
+///
+/// # Example usage
+///
+/// ```
+/// use colpetto::{Libinput, LogCategory, Logger};
+///
+/// let logger = Logger::structured(|record| {
+///     if record.category == LogCategory::KernelBug {
+///         eprintln!("kernel bug on {:?}: {}", record.device_sysname, record.message);
+///     }
+/// });
+///
+/// let libinput = Libinput::with_interface(interface, Some(logger))?;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct LogRecord {
+    /// The priority of the message
+    pub priority: LogPriority,
+    /// The category libinput tagged the message with
+    pub category: LogCategory,
+    /// The sysname of the device the message is about, e.g. `event5`
+    pub device_sysname: Option<String>,
+    /// The message, without the device and category prefixes and the trailing newline
+    pub message: String,
+}
+
+impl LogRecord {
+    /// Parses a formatted libinput log message
+    pub fn parse(priority: LogPriority, message: &str) -> Self {
+        let message = message.trim_ascii_end();
+
+        let (device_sysname, rest) = match split_device_prefix(message) {
+            Some((sysname, rest)) => (Some(sysname.to_owned()), rest),
+            None => (None, message),
+        };
+
+        // libinput only names the device in messages above debug
+        let has_device_name = device_sysname.is_some() && priority > LogPriority::Debug;
+
+        let (category, rest) =
+            split_category(rest, has_device_name).unwrap_or((LogCategory::General, rest));
+
+        Self {
+            priority,
+            category,
+            device_sysname,
+            message: rest.to_owned(),
+        }
+    }
+
+    /// Builds an untagged record for messages emitted by colpetto itself
+    pub(crate) fn colpetto(
+        priority: LogPriority,
+        device_sysname: Option<String>,
+        message: String,
+    ) -> Self {
+        Self {
+            priority,
+            category: LogCategory::General,
+            device_sysname,
+            message,
+        }
+    }
+}
+
+/// Splits `event5  - rest` into the sysname and the rest of the message
+fn split_device_prefix(message: &str) -> Option<(&str, &str)> {
+    let (prefix, rest) = message.split_once(" - ")?;
+    let sysname = prefix.trim_ascii_end();
 
-/// The function type to pass as a logger to libinput
-pub type Logger = Option<unsafe extern "C" fn(sys::libinput_log_priority, *const c_char)>;
+    let is_sysname = sysname.starts_with("event")
+        && sysname.len() > "event".len()
+        && sysname["event".len()..].bytes().all(|b| b.is_ascii_digit());
+
+    is_sysname.then_some((sysname, rest))
+}
+
+/// Finds a category tag at the start of the message, or right after the `Device Name: ` prefix if there is one.
+/// Tags anywhere else are part of the message.
+fn split_category(message: &str, has_device_name: bool) -> Option<(LogCategory, &str)> {
+    let tagged = match has_device_name {
+        true => message.split_once(": ")?.1,
+        false => message,
+    };
+
+    LogCategory::TAGS
+        .iter()
+        .find_map(|&(tag, category)| Some((category, tagged.strip_prefix(tag)?)))
+}
+
+static NEXT_CONTEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Allocates the process-wide unique id of a new context
+pub(crate) fn next_context_id() -> u64 {
+    NEXT_CONTEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
 
 unsafe extern "C" {
-    fn colpetto_inner_set_log_callback(callback: Logger);
     fn colpetto_inner_get_log_handler() -> sys::libinput_log_handler;
 }
 
-pub(crate) fn setup_logger(libinput: *mut sys::libinput, logger: Logger) {
-    if logger.is_none() {
-        return;
+/// Called by the C log handler once the message has been formatted
+#[unsafe(no_mangle)]
+unsafe extern "C" fn colpetto_inner_log_message(
+    libinput: *mut sys::libinput,
+    priority: sys::libinput_log_priority,
+    message: *const c_char,
+) {
+    // Safety: the handler is only installed on contexts created by colpetto
+    let handler = unsafe { Handler::from_context(libinput) };
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        // Safety guaranted by libinput
+        let message = unsafe { CStr::from_ptr(message) }.to_string_lossy();
+        let message = message.trim_ascii_end();
+
+        handler.emit(
+            &LogRecord::parse(LogPriority::from_raw(priority), message),
+            message,
+        );
+    }));
+
+    if let Err(payload) = result {
+        handler.store_panic("log", payload);
     }
+}
 
-    unsafe {
-        colpetto_inner_set_log_callback(logger);
+impl Handler {
+    /// Logs a message emitted by colpetto itself, formatted like the libinput ones for loggers built with [`Logger::new`]
+    pub(crate) fn log(&self, record: &LogRecord) {
+        let message = match &record.device_sysname {
+            Some(sysname) => format!("{sysname} - {}", record.message),
+            None => record.message.clone(),
+        };
+
+        self.emit(record, &message);
     }
 
-    unsafe {
-        sys::libinput_log_set_priority(
-            libinput,
-            sys::libinput_log_priority::LIBINPUT_LOG_PRIORITY_DEBUG,
-        );
+    /// Records `record` in the history of the context and forwards it to its logger
+    fn emit(&self, record: &LogRecord, message: &str) {
+        self.history.record_log(record);
+
+        match &self.logger {
+            Some(logger) => (logger.inner)(self.id, record, message),
+            // Mirrors the default libinput handler
+            None => match &record.device_sysname {
+                Some(sysname) => eprintln!(
+                    "libinput {}: {sysname} - {}",
+                    record.priority, record.message
+                ),
+                None => eprintln!("libinput {}: {}", record.priority, record.message),
+            },
+        }
     }
+}
 
+/// Routes the log messages of `libinput` to its handler, which records them and forwards them to its logger.
+/// With a logger, messages up to [`LogPriority::Debug`] are enabled and filtering is left to the logger,
+/// otherwise the libinput default of [`LogPriority::Error`] is kept and messages are printed to stderr.
+pub(crate) fn setup_logger(libinput: *mut sys::libinput, has_logger: bool) {
     unsafe {
+        if has_logger {
+            sys::libinput_log_set_priority(libinput, LogPriority::Debug.as_raw());
+        }
+
         sys::libinput_log_set_handler(libinput, colpetto_inner_get_log_handler());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_untagged() {
+        let record = LogRecord::parse(LogPriority::Info, "libinput started\n");
+
+        assert_eq!(record.category, LogCategory::General);
+        assert_eq!(record.device_sysname, None);
+        assert_eq!(record.message, "libinput started");
+    }
+
+    #[test]
+    fn parse_device_prefix() {
+        let record = LogRecord::parse(LogPriority::Debug, "event5  - tap: touch 0 down\n");
+
+        assert_eq!(record.device_sysname.as_deref(), Some("event5"));
+        assert_eq!(record.message, "tap: touch 0 down");
+        assert_eq!(record.category, LogCategory::General);
+    }
+
+    #[test]
+    fn parse_category_after_device_name() {
+        let record = LogRecord::parse(
+            LogPriority::Error,
+            "event7  - SynPS/2 Synaptics TouchPad: kernel bug: Touch jump detected and discarded.\n",
+        );
+
+        assert_eq!(record.device_sysname.as_deref(), Some("event7"));
+        assert_eq!(record.category, LogCategory::KernelBug);
+        assert_eq!(record.message, "Touch jump detected and discarded.");
+    }
+
+    #[test]
+    fn parse_leading_category() {
+        let record = LogRecord::parse(
+            LogPriority::Error,
+            "client bug: event processing lagging behind by 32ms, your system is too slow\n",
+        );
+
+        assert_eq!(record.device_sysname, None);
+        assert_eq!(record.category, LogCategory::ClientBug);
+        assert_eq!(
+            record.message,
+            "event processing lagging behind by 32ms, your system is too slow"
+        );
+    }
+
+    #[test]
+    fn parse_ignores_non_sysname_prefix() {
+        let record = LogRecord::parse(LogPriority::Info, "eventX - quirks: no match");
+
+        assert_eq!(record.device_sysname, None);
+        assert_eq!(record.category, LogCategory::General);
+        assert_eq!(record.message, "eventX - quirks: no match");
+    }
+
+    #[test]
+    fn parse_ignores_tags_past_the_prefix() {
+        for (priority, message, expected) in [
+            (
+                LogPriority::Info,
+                "event5  - Mouse: config: quirks: none applied",
+                "Mouse: config: quirks: none applied",
+            ),
+            (
+                LogPriority::Debug,
+                "event5  - config: kernel bug: not a tag",
+                "config: kernel bug: not a tag",
+            ),
+            (
+                LogPriority::Error,
+                "failed to open: libinput bug: not a tag",
+                "failed to open: libinput bug: not a tag",
+            ),
+        ] {
+            let record = LogRecord::parse(priority, message);
+
+            assert_eq!(record.category, LogCategory::General, "{message}");
+            assert_eq!(record.message, expected);
+        }
+    }
+
+    #[test]
+    fn parse_device_name_only_above_debug() {
+        // Debug messages carry no device name, so a name-like prefix is part of the message
+        let record = LogRecord::parse(LogPriority::Debug, "event5  - quirks: loaded 3 files");
+        assert_eq!(record.category, LogCategory::Quirks);
+        assert_eq!(record.message, "loaded 3 files");
+
+        // Above debug the first word is the device name, even if it looks like a tag
+        let record = LogRecord::parse(LogPriority::Info, "event5  - quirks: loaded 3 files");
+        assert_eq!(record.category, LogCategory::General);
+        assert_eq!(record.message, "quirks: loaded 3 files");
+
+        let record = LogRecord::parse(
+            LogPriority::Info,
+            "event5  - Logitech Mouse: quirks: MatchName applied",
+        );
+        assert_eq!(record.category, LogCategory::Quirks);
+        assert_eq!(record.message, "MatchName applied");
+    }
+}
@@ -0,0 +1,302 @@
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::{CString, OsStr, OsString},
+    fs, io,
+    mem::MaybeUninit,
+    os::{
+        fd::{AsRawFd, OwnedFd, RawFd},
+        unix::ffi::{OsStrExt, OsStringExt},
+    },
+    path::{Path, PathBuf},
+};
+
+use rustix::{
+    fs::inotify::{self, CreateFlags, ReadFlags, WatchFlags},
+    io::Errno,
+};
+
+use crate::{Device, sys};
+
+/// Hotplug for path contexts without udev, adding and removing the `event*` nodes of a directory
+/// as they appear and disappear.
+///
+/// The inotify fd lives as long as the watcher, so it stays valid for event streams polling it
+/// when a different directory is watched.
+pub(crate) struct PathWatcher {
+    inotify: OwnedFd,
+    dir: PathBuf,
+    /// The watch descriptor of `dir`, events of previous watches are ignored
+    wd: i32,
+    /// The devices added by the watcher, by node name
+    devices: HashMap<OsString, Device>,
+    /// While the context is suspended changes are drained but not applied, the rescan on resume catches up
+    suspended: bool,
+}
+
+impl PathWatcher {
+    pub(crate) fn new(dir: &Path) -> io::Result<Self> {
+        let inotify = inotify::init(CreateFlags::CLOEXEC | CreateFlags::NONBLOCK)?;
+        let wd = add_watch(&inotify, dir)?;
+
+        Ok(Self {
+            inotify,
+            dir: dir.to_owned(),
+            wd,
+            devices: HashMap::new(),
+            suspended: false,
+        })
+    }
+
+    /// Watches `dir` instead of the current directory, removing the devices added so far.
+    /// On failure the current watch is left untouched.
+    pub(crate) fn watch(&mut self, dir: &Path) -> io::Result<()> {
+        let wd = add_watch(&self.inotify, dir)?;
+
+        // Watching the same directory again returns the same descriptor
+        if wd != self.wd {
+            // The old watch may already be gone if its directory was deleted
+            let _ = inotify::remove_watch(&self.inotify, self.wd);
+        }
+
+        self.remove_all();
+        self.dir = dir.to_owned();
+        self.wd = wd;
+
+        Ok(())
+    }
+
+    pub(crate) fn fd(&self) -> RawFd {
+        self.inotify.as_raw_fd()
+    }
+
+    /// Removes every device added by the watcher and stops applying changes until [`resume`](Self::resume)
+    pub(crate) fn suspend(&mut self) {
+        self.remove_all();
+        self.suspended = true;
+    }
+
+    /// Applies changes again, adding the nodes that appeared while suspended
+    pub(crate) fn resume(&mut self, libinput: *mut sys::libinput) -> io::Result<()> {
+        self.suspended = false;
+        self.rescan(libinput)
+    }
+
+    /// Adds the nodes currently present and removes the ones that disappeared, does nothing while suspended
+    pub(crate) fn rescan(&mut self, libinput: *mut sys::libinput) -> io::Result<()> {
+        if self.suspended {
+            return Ok(());
+        }
+
+        let present = self.event_nodes()?;
+
+        let vanished: Vec<_> = self
+            .devices
+            .keys()
+            .filter(|name| !present.contains(*name))
+            .cloned()
+            .collect();
+
+        for name in vanished {
+            self.remove(&name);
+        }
+
+        for name in present {
+            self.add(libinput, name);
+        }
+
+        Ok(())
+    }
+
+    /// Reads the pending inotify events and applies them, never blocks.
+    /// While suspended the events are only drained, so the fd does not stay readable.
+    pub(crate) fn dispatch(&mut self, libinput: *mut sys::libinput) -> io::Result<()> {
+        let (changes, overflow) = self.read_changes()?;
+
+        if self.suspended {
+            return Ok(());
+        }
+
+        // Events were lost, the directory is the only source of truth left
+        if overflow {
+            return self.rescan(libinput);
+        }
+
+        for (events, name) in changes {
+            if !is_event_node(&name) {
+                continue;
+            }
+
+            if events.intersects(ReadFlags::DELETE | ReadFlags::MOVED_FROM) {
+                self.remove(&name);
+            } else {
+                // Nodes may be created before their permissions are set, attribute changes retry the open
+                self.add(libinput, name);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads the pending inotify events of the current watch, and whether the queue overflowed
+    fn read_changes(&mut self) -> io::Result<(Vec<(ReadFlags, OsString)>, bool)> {
+        let mut buffer = [MaybeUninit::uninit(); 4096];
+        let mut reader = inotify::Reader::new(&self.inotify, &mut buffer);
+
+        let mut changes = Vec::new();
+        let mut overflow = false;
+
+        loop {
+            match reader.next() {
+                Ok(event) if event.events().contains(ReadFlags::QUEUE_OVERFLOW) => overflow = true,
+                Ok(event) if event.wd() != self.wd => {}
+                Ok(event) => {
+                    if let Some(name) = event.file_name() {
+                        let name = OsStr::from_bytes(name.to_bytes()).to_owned();
+                        changes.push((event.events(), name));
+                    }
+                }
+                Err(Errno::AGAIN) => break,
+                Err(errno) => return Err(errno.into()),
+            }
+        }
+
+        Ok((changes, overflow))
+    }
+
+    /// The `event*` nodes currently in the watched directory
+    fn event_nodes(&self) -> io::Result<HashSet<OsString>> {
+        Ok(fs::read_dir(&self.dir)?
+            .filter_map(|entry| Some(entry.ok()?.file_name()))
+            .filter(|name| is_event_node(name))
+            .collect())
+    }
+
+    /// Removes every device added by the watcher
+    fn remove_all(&mut self) {
+        for (_, device) in self.devices.drain() {
+            unsafe { sys::libinput_path_remove_device(device.as_raw()) };
+        }
+    }
+
+    fn add(&mut self, libinput: *mut sys::libinput, name: OsString) {
+        if self.devices.contains_key(&name) {
+            return;
+        }
+
+        let Ok(path) = CString::new(self.dir.join(&name).into_os_string().into_vec()) else {
+            return;
+        };
+
+        // Failures are logged by libinput and recorded by the open callback
+        let raw = unsafe { sys::libinput_path_add_device(libinput, path.as_ptr()) };
+
+        if !raw.is_null() {
+            self.devices.insert(name, unsafe { Device::from_raw(raw) });
+        }
+    }
+
+    fn remove(&mut self, name: &OsStr) {
+        if let Some(device) = self.devices.remove(name) {
+            unsafe { sys::libinput_path_remove_device(device.as_raw()) };
+        }
+    }
+}
+
+fn add_watch(inotify: &OwnedFd, dir: &Path) -> io::Result<i32> {
+    let wd = inotify::add_watch(
+        inotify,
+        dir,
+        WatchFlags::CREATE
+            | WatchFlags::DELETE
+            | WatchFlags::ATTRIB
+            | WatchFlags::MOVED_TO
+            | WatchFlags::MOVED_FROM
+            | WatchFlags::ONLYDIR,
+    )?;
+
+    Ok(wd)
+}
+
+fn is_event_node(name: &OsStr) -> bool {
+    name.as_bytes()
+        .strip_prefix(b"event")
+        .is_some_and(|number| !number.is_empty() && number.iter().all(u8::is_ascii_digit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_nodes() {
+        for name in ["event0", "event7", "event123"] {
+            assert!(is_event_node(OsStr::new(name)), "{name}");
+        }
+
+        for name in [
+            "", "event", "event-1", "event1a", "mouse0", "js0", "by-id", "xevent1",
+        ] {
+            assert!(!is_event_node(OsStr::new(name)), "{name}");
+        }
+    }
+
+    #[test]
+    fn rewatching_keeps_the_inotify_fd() {
+        let root =
+            std::env::temp_dir().join(format!("colpetto-path-watcher-{}", std::process::id()));
+        let (first, second) = (root.join("first"), root.join("second"));
+        fs::create_dir_all(&first).unwrap();
+        fs::create_dir_all(&second).unwrap();
+
+        let mut watcher = PathWatcher::new(&first).unwrap();
+        let fd = watcher.fd();
+
+        watcher.watch(&second).unwrap();
+        assert_eq!(watcher.fd(), fd);
+        assert_eq!(watcher.dir, second);
+
+        watcher.watch(&second).unwrap();
+        assert_eq!(watcher.dir, second);
+
+        // A failed watch leaves the current one in place
+        assert!(watcher.watch(&root.join("missing")).is_err());
+        assert_eq!(watcher.dir, second);
+        assert_eq!(watcher.fd(), fd);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn nodes_created_while_suspended_wait_for_resume() {
+        let dir = std::env::temp_dir().join(format!(
+            "colpetto-path-watcher-suspend-{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut watcher = PathWatcher::new(&dir).unwrap();
+        watcher.suspend();
+
+        fs::write(dir.join("event0"), "").unwrap();
+
+        // A suspended watcher must not touch the context, a null one would crash libinput
+        watcher.dispatch(std::ptr::null_mut()).unwrap();
+        watcher.rescan(std::ptr::null_mut()).unwrap();
+        assert!(watcher.devices.is_empty());
+
+        // The change was drained so the fd does not stay readable
+        let (changes, overflow) = watcher.read_changes().unwrap();
+        assert!(changes.is_empty());
+        assert!(!overflow);
+
+        // The rescan on resume adds it
+        assert!(
+            watcher
+                .event_nodes()
+                .unwrap()
+                .contains(OsStr::new("event0"))
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,312 @@
+use std::{collections::HashMap, path::PathBuf};
+
+/// A typed view over the udev properties commonly used to classify and configure input devices.
+///
+/// The properties are set by udev's `input_id` builtin, by hwdb entries and by libinput's own udev rules.
+/// Use [`Device::properties`](crate::Device::properties) to read them from a device, or [`from_properties`](Self::from_properties)
+/// to parse an arbitrary set of key/value pairs.
+///
+/// Properties that are missing or malformed are left as `None` or empty, parsing never fails.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[non_exhaustive]
+pub struct DeviceProperties {
+    /// The device node, e.g. `/dev/input/event5`
+    pub devnode: Option<PathBuf>,
+    /// The classification flags set by udev (`ID_INPUT_*`)
+    pub classification: Classification,
+    /// The resolutions supported by a mouse, from the `MOUSE_DPI` hwdb property
+    pub dpi: Vec<MouseDpi>,
+    /// The output the device should be mapped to, from the `WL_OUTPUT` property
+    pub output: Option<String>,
+    /// The physical device group, from the `LIBINPUT_DEVICE_GROUP` property
+    pub device_group: Option<String>,
+    /// The default calibration matrix, from the `LIBINPUT_CALIBRATION_MATRIX` property
+    pub calibration_matrix: Option<[f32; 6]>,
+    /// Whether libinput was told to ignore this device with `LIBINPUT_IGNORE_DEVICE`
+    pub ignore: bool,
+    /// The kernel ids of the device, from the `PRODUCT` property of the parent input device
+    pub input_id: Option<InputId>,
+    /// The bus the device is connected to, from `ID_BUS`, e.g. `usb` or `i8042`
+    pub bus: Option<String>,
+    /// The udev `ID_PATH` property, describing where the device is connected
+    pub id_path: Option<String>,
+    /// The udev `ID_SERIAL` property, usually only available on usb devices
+    pub id_serial: Option<String>,
+}
+
+/// The classification of an input device as done by udev's `input_id` builtin.
+///
+/// A device may have more than one class, e.g. a keyboard with an integrated touchpad.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[non_exhaustive]
+pub struct Classification {
+    /// `ID_INPUT`, set on every input device
+    pub input: bool,
+    /// `ID_INPUT_KEY`, the device has at least some keys
+    pub key: bool,
+    /// `ID_INPUT_KEYBOARD`, the device is a full keyboard
+    pub keyboard: bool,
+    /// `ID_INPUT_MOUSE`
+    pub mouse: bool,
+    /// `ID_INPUT_POINTINGSTICK`
+    pub pointingstick: bool,
+    /// `ID_INPUT_TOUCHPAD`
+    pub touchpad: bool,
+    /// `ID_INPUT_TOUCHSCREEN`
+    pub touchscreen: bool,
+    /// `ID_INPUT_TABLET`
+    pub tablet: bool,
+    /// `ID_INPUT_TABLET_PAD`
+    pub tablet_pad: bool,
+    /// `ID_INPUT_JOYSTICK`
+    pub joystick: bool,
+    /// `ID_INPUT_ACCELEROMETER`
+    pub accelerometer: bool,
+    /// `ID_INPUT_SWITCH`
+    pub switch: bool,
+    /// `ID_INPUT_TRACKBALL`
+    pub trackball: bool,
+}
+
+/// A single resolution entry of the `MOUSE_DPI` property, e.g. `*800@125`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MouseDpi {
+    /// The resolution in dots per inch
+    pub dpi: u32,
+    /// The polling frequency in Hz, if specified
+    pub frequency: Option<u32>,
+    /// Whether this is the default resolution of the device
+    pub default: bool,
+}
+
+/// The ids of an input device as reported by the kernel
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InputId {
+    /// The bus type, as in `linux/input.h`
+    pub bustype: u32,
+    /// The vendor id
+    pub vendor: u32,
+    /// The product id
+    pub product: u32,
+    /// The version
+    pub version: u32,
+}
+
+impl InputId {
+    /// Parses the `PRODUCT` property of an input device, formatted as `bustype/vendor/product/version` in hex
+    pub fn from_product(product: &str) -> Option<Self> {
+        let mut ids = product
+            .split('/')
+            .map(|id| u32::from_str_radix(id, 16).ok());
+
+        Some(Self {
+            bustype: ids.next()??,
+            vendor: ids.next()??,
+            product: ids.next()??,
+            version: ids.next()??,
+        })
+    }
+}
+
+impl DeviceProperties {
+    /// Parses the properties of a device from a set of udev key/value pairs.
+    ///
+    /// The `PRODUCT` key is used to fill [`input_id`](Self::input_id), udev only sets it on the parent input device.
+    pub fn from_properties<'a>(properties: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let properties: HashMap<_, _> = properties.into_iter().collect();
+
+        let string = |key| {
+            properties
+                .get(key)
+                .map(|value| value.to_string())
+                .filter(|value| !value.is_empty())
+        };
+        let flag = |key| properties.get(key).is_some_and(|value| *value == "1");
+
+        Self {
+            devnode: properties.get("DEVNAME").map(PathBuf::from),
+            classification: Classification {
+                input: flag("ID_INPUT"),
+                key: flag("ID_INPUT_KEY"),
+                keyboard: flag("ID_INPUT_KEYBOARD"),
+                mouse: flag("ID_INPUT_MOUSE"),
+                pointingstick: flag("ID_INPUT_POINTINGSTICK"),
+                touchpad: flag("ID_INPUT_TOUCHPAD"),
+                touchscreen: flag("ID_INPUT_TOUCHSCREEN"),
+                tablet: flag("ID_INPUT_TABLET"),
+                tablet_pad: flag("ID_INPUT_TABLET_PAD"),
+                joystick: flag("ID_INPUT_JOYSTICK"),
+                accelerometer: flag("ID_INPUT_ACCELEROMETER"),
+                switch: flag("ID_INPUT_SWITCH"),
+                trackball: flag("ID_INPUT_TRACKBALL"),
+            },
+            dpi: properties
+                .get("MOUSE_DPI")
+                .map(|dpi| parse_mouse_dpi(dpi))
+                .unwrap_or_default(),
+            output: string("WL_OUTPUT"),
+            device_group: string("LIBINPUT_DEVICE_GROUP"),
+            calibration_matrix: properties
+                .get("LIBINPUT_CALIBRATION_MATRIX")
+                .and_then(|matrix| parse_calibration_matrix(matrix)),
+            ignore: flag("LIBINPUT_IGNORE_DEVICE"),
+            input_id: properties
+                .get("PRODUCT")
+                .and_then(|product| InputId::from_product(product)),
+            bus: string("ID_BUS"),
+            id_path: string("ID_PATH"),
+            id_serial: string("ID_SERIAL"),
+        }
+    }
+
+    /// The default resolution of a mouse. If no entry is marked as default the first one is used.
+    pub fn default_dpi(&self) -> Option<MouseDpi> {
+        self.dpi
+            .iter()
+            .find(|dpi| dpi.default)
+            .or(self.dpi.first())
+            .copied()
+    }
+}
+
+/// Parses a `MOUSE_DPI` value, a space separated list of `dpi[@frequency]` entries with the default marked by `*`
+fn parse_mouse_dpi(value: &str) -> Vec<MouseDpi> {
+    value
+        .split_ascii_whitespace()
+        .filter_map(|entry| {
+            let (default, entry) = match entry.strip_prefix('*') {
+                Some(entry) => (true, entry),
+                None => (false, entry),
+            };
+
+            let (dpi, frequency) = match entry.split_once('@') {
+                Some((dpi, frequency)) => (dpi, Some(frequency.parse().ok()?)),
+                None => (entry, None),
+            };
+
+            Some(MouseDpi {
+                dpi: dpi.parse().ok()?,
+                frequency,
+                default,
+            })
+        })
+        .collect()
+}
+
+/// Parses a `LIBINPUT_CALIBRATION_MATRIX` value, six space separated floats
+fn parse_calibration_matrix(value: &str) -> Option<[f32; 6]> {
+    let mut matrix = [0.0; 6];
+    let mut values = value.split_ascii_whitespace();
+
+    for slot in &mut matrix {
+        *slot = values.next()?.parse().ok()?;
+    }
+
+    values.next().is_none().then_some(matrix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dpi(dpi: u32, frequency: Option<u32>, default: bool) -> MouseDpi {
+        MouseDpi {
+            dpi,
+            frequency,
+            default,
+        }
+    }
+
+    #[test]
+    fn mouse_dpi() {
+        assert_eq!(
+            parse_mouse_dpi("400 *800@125"),
+            [dpi(400, None, false), dpi(800, Some(125), true)]
+        );
+        assert_eq!(
+            parse_mouse_dpi(" 1000@500  *2000@1000 "),
+            [dpi(1000, Some(500), false), dpi(2000, Some(1000), true)]
+        );
+        assert_eq!(parse_mouse_dpi(""), []);
+    }
+
+    #[test]
+    fn mouse_dpi_skips_malformed_entries() {
+        assert_eq!(
+            parse_mouse_dpi("abc 400@ *800@fast 1200 -5 **1600"),
+            [dpi(1200, None, false)]
+        );
+    }
+
+    #[test]
+    fn default_dpi() {
+        let properties = DeviceProperties::from_properties([("MOUSE_DPI", "400 *800@125 1600")]);
+        assert_eq!(properties.default_dpi(), Some(dpi(800, Some(125), true)));
+
+        let properties = DeviceProperties::from_properties([("MOUSE_DPI", "400 1600")]);
+        assert_eq!(properties.default_dpi(), Some(dpi(400, None, false)));
+
+        assert_eq!(DeviceProperties::default().default_dpi(), None);
+    }
+
+    #[test]
+    fn calibration_matrix() {
+        assert_eq!(
+            parse_calibration_matrix("1 0 0 0 1 0"),
+            Some([1.0, 0.0, 0.0, 0.0, 1.0, 0.0])
+        );
+        assert_eq!(
+            parse_calibration_matrix("  -1.5 0.25 1e1\t0 1 -0 "),
+            Some([-1.5, 0.25, 10.0, 0.0, 1.0, 0.0])
+        );
+    }
+
+    #[test]
+    fn calibration_matrix_rejects_wrong_length_or_values() {
+        assert_eq!(parse_calibration_matrix(""), None);
+        assert_eq!(parse_calibration_matrix("1 0 0 0 1"), None);
+        assert_eq!(parse_calibration_matrix("1 0 0 0 1 0 0"), None);
+        assert_eq!(parse_calibration_matrix("1 0 0 0 1 x"), None);
+    }
+
+    #[test]
+    fn from_properties() {
+        let properties = DeviceProperties::from_properties([
+            ("DEVNAME", "/dev/input/event5"),
+            ("ID_INPUT", "1"),
+            ("ID_INPUT_MOUSE", "1"),
+            ("ID_INPUT_KEY", "0"),
+            ("PRODUCT", "3/46d/c52b/111"),
+            ("WL_OUTPUT", ""),
+            ("ID_BUS", "usb"),
+        ]);
+
+        assert_eq!(properties.devnode, Some(PathBuf::from("/dev/input/event5")));
+        assert!(properties.classification.input);
+        assert!(properties.classification.mouse);
+        assert!(!properties.classification.key);
+        assert_eq!(
+            properties.input_id,
+            Some(InputId {
+                bustype: 0x3,
+                vendor: 0x46d,
+                product: 0xc52b,
+                version: 0x111,
+            })
+        );
+        assert_eq!(properties.output, None);
+        assert_eq!(properties.bus.as_deref(), Some("usb"));
+    }
+
+    #[test]
+    fn input_id_rejects_malformed_product() {
+        assert_eq!(InputId::from_product("3/46d/c52b"), None);
+        assert_eq!(InputId::from_product("3/46d/xyz/111"), None);
+    }
+}
@@ -0,0 +1,628 @@
+//! Recording events to a file and replaying them, enabled by the `record` feature.
+//!
+//! A recording starts with a small header identifying the format and its version, followed by one
+//! [`Record`] per event, encoded with MessagePack. Records hold the [owned](crate::event::owned) copy of the
+//! event, which includes the device it comes from, and a timestamp in microseconds. Device added and removed
+//! events are recorded like any other event, so a recording describes the devices present during the session.
+//!
+//! [`Recording`] wraps an [`EventStream`](crate::EventStream), writing every event it yields, and [`ReplayStream`]
+//! yields the recorded events back with their original timing, or faster, through the same [`Stream`] interface.
+//! No input device or libinput context is needed to replay a recording.
+//!
+//! # Example usage
+//!
+//! ```
+//! use std::{fs::File, io::BufWriter};
+//!
+//! use colpetto::record::{Recorder, Recording, ReplayStream, Timing};
+//! use tokio_stream::StreamExt;
+//!
+//! let file = BufWriter::new(File::create("session.rec")?);
+//! let mut recording = Recording::new(libinput.event_stream()?, Recorder::new(file)?);
+//!
+//! while let Some(event) = recording.try_next().await? {
+//!     println!("Recorded {}", event.event_type());
+//! }
+//!
+//! // Later, possibly on another machine
+//! let mut replay = ReplayStream::open("session.rec", Timing::Accelerated(10.0))?;
+//!
+//! while let Some(event) = replay.try_next().await? {
+//!     println!("Replayed {} from {}", event.event_type(), event.device().name);
+//! }
+//! ```
+
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Read, Write},
+    path::Path,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures_core::Stream;
+use rustix::time::{ClockId, clock_gettime};
+use tokio::time::{Instant, Sleep};
+
+use crate::{Error, Event, OwnedEvent, schema};
+
+/// Identifies a recording, at the start of every file
+pub const MAGIC: [u8; 8] = *b"colpetto";
+
+/// The longest delay between two replayed events, the one of an event recorded at the end of time
+const MAX_DELAY: Duration = Duration::from_micros(u64::MAX);
+
+/// The current version of the recording format.
+/// The version of the [schema](crate::schema) the records are encoded with is stored separately.
+pub const VERSION: u32 = 1;
+
+/// Error returned when recording or replaying fails
+#[derive(Debug, thiserror::Error)]
+pub enum RecordError {
+    /// Reading or writing the recording failed
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// The recorded stream returned an error
+    #[error(transparent)]
+    Libinput(#[from] Error),
+    /// An event could not be encoded
+    #[error("Failed to encode record: {0}")]
+    Encode(#[from] rmp_serde::encode::Error),
+    /// A record could not be decoded, the recording is probably truncated or corrupted
+    #[error("Failed to decode record: {0}")]
+    Decode(#[from] rmp_serde::decode::Error),
+    /// The data is not a recording
+    #[error("Not a colpetto recording")]
+    InvalidMagic,
+    /// The recording was written by a newer version of colpetto
+    #[error("Unsupported recording version {format}, schema version {schema}")]
+    UnsupportedVersion {
+        /// The version of the recording format
+        format: u32,
+        /// The version of the schema of the records
+        schema: u32,
+    },
+}
+
+/// A recorded event
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[non_exhaustive]
+pub struct Record {
+    /// The time of the event in microseconds, on the monotonic clock.
+    /// Events without a timestamp, e.g. device events, are stamped when recorded.
+    pub time_usec: u64,
+    /// The event
+    pub event: OwnedEvent,
+}
+
+impl Record {
+    /// Creates a record of `event`, stamping it with the current time if it carries no timestamp
+    pub fn new(event: OwnedEvent) -> Self {
+        let time_usec = event.time_usec().unwrap_or_else(|| {
+            let now = clock_gettime(ClockId::Monotonic);
+            now.tv_sec as u64 * 1_000_000 + now.tv_nsec as u64 / 1_000
+        });
+
+        Self { time_usec, event }
+    }
+}
+
+/// Writes events to a recording
+#[derive(Debug)]
+pub struct Recorder<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> Recorder<W> {
+    /// Starts a recording by writing its header to `writer`.
+    /// Records are written one by one, wrapping the writer in a [`BufWriter`](std::io::BufWriter) is recommended.
+    pub fn new(mut writer: W) -> Result<Self, RecordError> {
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&schema::VERSION.to_le_bytes())?;
+
+        Ok(Self { writer })
+    }
+
+    /// Records an event, see [`Event::to_owned`]
+    pub fn record(&mut self, event: &Event) -> Result<(), RecordError> {
+        self.write(&Record::new(event.to_owned()))
+    }
+
+    /// Writes a record
+    pub fn write(&mut self, record: &Record) -> Result<(), RecordError> {
+        // Field names are kept so records stay readable when fields are added
+        rmp_serde::encode::write_named(&mut self.writer, record)?;
+        Ok(())
+    }
+
+    /// Flushes the underlying writer
+    pub fn flush(&mut self) -> Result<(), RecordError> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// Returns the underlying writer, without flushing it
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// A stream recording every event yielded by the wrapped stream, usually an [`EventStream`](crate::EventStream).
+///
+/// Events are passed through unchanged. The recording is flushed when the stream is dropped.
+#[derive(Debug)]
+pub struct Recording<S, W: Write> {
+    stream: S,
+    recorder: Recorder<W>,
+}
+
+impl<S, W: Write> Recording<S, W> {
+    /// Records the events yielded by `stream` with `recorder`
+    pub fn new(stream: S, recorder: Recorder<W>) -> Self {
+        Self { stream, recorder }
+    }
+
+    /// Returns the recorder
+    pub fn recorder(&mut self) -> &mut Recorder<W> {
+        &mut self.recorder
+    }
+}
+
+impl<S, W> Stream for Recording<S, W>
+where
+    S: Stream<Item = Result<Event, Error>> + Unpin,
+    W: Write + Unpin,
+{
+    type Item = Result<Event, RecordError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+
+        let Poll::Ready(item) = Pin::new(&mut this.stream).poll_next(cx) else {
+            return Poll::Pending;
+        };
+
+        Poll::Ready(item.map(|event| {
+            let event = event?;
+            this.recorder.record(&event)?;
+            Ok(event)
+        }))
+    }
+}
+
+impl<S, W: Write> Drop for Recording<S, W> {
+    fn drop(&mut self) {
+        // Errors can only be reported by flushing explicitly
+        let _ = self.recorder.flush();
+    }
+}
+
+/// Reads the records of a recording, one at a time
+#[derive(Debug)]
+pub struct Reader<R> {
+    reader: BufReader<R>,
+    schema: u32,
+}
+
+impl<R: Read> Reader<R> {
+    /// Reads and checks the header of a recording
+    pub fn new(reader: R) -> Result<Self, RecordError> {
+        let mut reader = BufReader::new(reader);
+
+        let mut magic = [0; MAGIC.len()];
+        reader.read_exact(&mut magic).map_err(|error| {
+            if error.kind() == io::ErrorKind::UnexpectedEof {
+                RecordError::InvalidMagic
+            } else {
+                error.into()
+            }
+        })?;
+
+        if magic != MAGIC {
+            return Err(RecordError::InvalidMagic);
+        }
+
+        let format = read_u32(&mut reader)?;
+        let schema = read_u32(&mut reader)?;
+
+        if format > VERSION || schema > schema::VERSION {
+            return Err(RecordError::UnsupportedVersion { format, schema });
+        }
+
+        Ok(Self { reader, schema })
+    }
+
+    /// The version of the [schema](crate::schema) of the records
+    pub fn schema_version(&self) -> u32 {
+        self.schema
+    }
+
+    /// Reads the next record, `None` at the end of the recording
+    pub fn read(&mut self) -> Result<Option<Record>, RecordError> {
+        if self.reader.fill_buf()?.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(rmp_serde::from_read(&mut self.reader)?))
+    }
+}
+
+impl Reader<File> {
+    /// Opens the recording at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, RecordError> {
+        Self::new(File::open(path)?)
+    }
+}
+
+impl<R: Read> Iterator for Reader<R> {
+    type Item = Result<Record, RecordError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read().transpose()
+    }
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+/// How fast a [`ReplayStream`] yields its events
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Timing {
+    /// With the delays between events they were recorded with
+    Original,
+    /// With the recorded delays divided by the given factor, e.g. `2.0` replays twice as fast.
+    /// Factors that are not positive replay without delay, delays too long to represent are clamped.
+    Accelerated(f64),
+    /// Without any delay
+    Immediate,
+}
+
+/// A stream replaying a recording, see the [module documentation](self).
+///
+/// The first event is yielded immediately, the following ones are delayed relative to it according to the [`Timing`],
+/// so the replay does not drift even if the consumer is slow. Events recorded with decreasing timestamps are not delayed.
+///
+/// Records are read from the underlying reader as they are yielded, read errors end the stream after being yielded.
+/// Reads are blocking and done inside [`poll_next`](Stream::poll_next): buffered reads from a regular file are quick,
+/// but a reader that can stall, e.g. a pipe, blocks the runtime thread polling the stream.
+/// Read such recordings into memory first and replay them from a slice.
+#[derive(Debug)]
+pub struct ReplayStream<R> {
+    reader: Option<Reader<R>>,
+    timing: Timing,
+    /// The recorded time of the first event and when it was yielded
+    start: Option<(u64, Instant)>,
+    pending: Option<(Record, Pin<Box<Sleep>>)>,
+}
+
+impl<R: Read> ReplayStream<R> {
+    /// Replays the records of `reader`
+    pub fn new(reader: Reader<R>, timing: Timing) -> Self {
+        Self {
+            reader: Some(reader),
+            timing,
+            start: None,
+            pending: None,
+        }
+    }
+
+    fn delay(&self, time_usec: u64, start_usec: u64) -> Duration {
+        let elapsed = Duration::from_micros(time_usec.saturating_sub(start_usec));
+
+        match self.timing {
+            Timing::Original => elapsed,
+            // Dividing by a tiny factor overflows the delay
+            Timing::Accelerated(factor) if factor > 0.0 => {
+                Duration::try_from_secs_f64(elapsed.as_secs_f64() / factor)
+                    .map_or(MAX_DELAY, |delay| delay.min(MAX_DELAY))
+            }
+            Timing::Accelerated(_) | Timing::Immediate => Duration::ZERO,
+        }
+    }
+}
+
+impl ReplayStream<File> {
+    /// Replays the recording at `path`
+    pub fn open(path: impl AsRef<Path>, timing: Timing) -> Result<Self, RecordError> {
+        Ok(Self::new(Reader::open(path)?, timing))
+    }
+}
+
+impl<R: Read + Unpin> Stream for ReplayStream<R> {
+    type Item = Result<OwnedEvent, RecordError>;
+
+    /// # Panics
+    ///
+    /// Panics if polled outside of a tokio runtime with the time driver enabled, unless the timing is [`Timing::Immediate`]
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+
+        if this.pending.is_none() {
+            let Some(reader) = &mut this.reader else {
+                return Poll::Ready(None);
+            };
+
+            let record = match reader.read() {
+                Ok(Some(record)) => record,
+                Ok(None) => {
+                    this.reader = None;
+                    return Poll::Ready(None);
+                }
+                Err(error) => {
+                    this.reader = None;
+                    return Poll::Ready(Some(Err(error)));
+                }
+            };
+
+            let Some((start_usec, start)) = this.start else {
+                this.start = Some((record.time_usec, Instant::now()));
+                return Poll::Ready(Some(Ok(record.event)));
+            };
+
+            let delay = this.delay(record.time_usec, start_usec);
+
+            if delay.is_zero() {
+                return Poll::Ready(Some(Ok(record.event)));
+            }
+
+            this.pending = Some((record, Box::pin(tokio::time::sleep_until(start + delay))));
+        }
+
+        if let Some((_, sleep)) = &mut this.pending
+            && sleep.as_mut().poll(cx).is_pending()
+        {
+            return Poll::Pending;
+        }
+
+        Poll::Ready(this.pending.take().map(|(record, _)| Ok(record.event)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future::poll_fn, sync::Arc};
+
+    use super::*;
+    use crate::{
+        DeviceCapability, DeviceId, DeviceInfo,
+        event::{
+            KeyState, OwnedDeviceAddedEvent, OwnedDeviceEvent, OwnedKeyboardEvent,
+            OwnedKeyboardKeyEvent,
+        },
+    };
+
+    fn keyboard() -> Arc<DeviceInfo> {
+        Arc::new(DeviceInfo {
+            sysname: "event3".to_owned(),
+            name: "Test Keyboard".to_owned(),
+            vendor_id: 0x046d,
+            product_id: 0xc52b,
+            output_name: None,
+            physical_seat: "seat0".to_owned(),
+            logical_seat: "default".to_owned(),
+            capabilities: DeviceCapability::Keyboard.into(),
+            size: None,
+            id: DeviceId {
+                bustype: 0x03,
+                vendor: 0x046d,
+                product: 0xc52b,
+                name: "Test Keyboard".to_owned(),
+                id_path: None,
+                id_serial: None,
+            },
+        })
+    }
+
+    fn key(time_usec: u64, key: u32) -> Record {
+        Record::new(OwnedEvent::Keyboard(OwnedKeyboardEvent::Key(
+            OwnedKeyboardKeyEvent {
+                device: keyboard(),
+                time_usec,
+                key,
+                key_state: KeyState::Pressed,
+                seat_key_count: 1,
+            },
+        )))
+    }
+
+    fn session() -> Vec<Record> {
+        vec![
+            Record {
+                time_usec: 1_000_000,
+                event: OwnedEvent::Device(OwnedDeviceEvent::Added(OwnedDeviceAddedEvent {
+                    device: keyboard(),
+                })),
+            },
+            key(2_000_000, 30),
+            key(4_000_000, 48),
+            // Out of order timestamps are replayed without delay
+            key(3_000_000, 46),
+        ]
+    }
+
+    fn record(records: &[Record]) -> Vec<u8> {
+        let mut recorder = Recorder::new(Vec::new()).unwrap();
+
+        for record in records {
+            recorder.write(record).unwrap();
+        }
+
+        recorder.into_inner()
+    }
+
+    fn header(format: u32, schema: u32) -> Vec<u8> {
+        let mut header = MAGIC.to_vec();
+        header.extend_from_slice(&format.to_le_bytes());
+        header.extend_from_slice(&schema.to_le_bytes());
+        header
+    }
+
+    /// Replays `records`, returning the keys along with when they were yielded relative to the first event
+    async fn replay(records: &[Record], timing: Timing) -> Vec<(Option<u32>, Duration)> {
+        let bytes = record(records);
+        let mut replay = ReplayStream::new(Reader::new(bytes.as_slice()).unwrap(), timing);
+        let start = Instant::now();
+        let mut replayed = Vec::new();
+
+        while let Some(event) = poll_fn(|cx| Pin::new(&mut replay).poll_next(cx)).await {
+            let key = match event.unwrap() {
+                OwnedEvent::Keyboard(OwnedKeyboardEvent::Key(key)) => Some(key.key),
+                _ => None,
+            };
+
+            replayed.push((key, start.elapsed()));
+        }
+
+        replayed
+    }
+
+    #[test]
+    fn round_trip() {
+        let records = session();
+        let bytes = record(&records);
+
+        let reader = Reader::new(bytes.as_slice()).unwrap();
+        assert_eq!(reader.schema_version(), schema::VERSION);
+
+        let read: Vec<_> = reader.collect::<Result<_, _>>().unwrap();
+        assert_eq!(read, records);
+    }
+
+    #[test]
+    fn empty_recording() {
+        let bytes = record(&[]);
+
+        assert_eq!(bytes, header(VERSION, schema::VERSION));
+        assert!(
+            Reader::new(bytes.as_slice())
+                .unwrap()
+                .read()
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn rejects_bad_magic() {
+        for bytes in [&b""[..], b"colpet", b"notarecording\0\0\0\0"] {
+            assert!(
+                matches!(Reader::new(bytes), Err(RecordError::InvalidMagic)),
+                "{bytes:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let newer_format = header(VERSION + 1, schema::VERSION);
+        assert!(matches!(
+            Reader::new(newer_format.as_slice()),
+            Err(RecordError::UnsupportedVersion { format, schema })
+                if format == VERSION + 1 && schema == schema::VERSION
+        ));
+
+        let newer_schema = header(VERSION, schema::VERSION + 1);
+        assert!(matches!(
+            Reader::new(newer_schema.as_slice()),
+            Err(RecordError::UnsupportedVersion { format, schema })
+                if format == VERSION && schema == schema::VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn truncated_record_is_an_error() {
+        let mut bytes = record(&session());
+        bytes.pop();
+
+        let mut reader = Reader::new(bytes.as_slice()).unwrap();
+        for _ in 0..3 {
+            assert!(reader.read().unwrap().is_some());
+        }
+        assert!(matches!(reader.read(), Err(RecordError::Decode(_))));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn replay_original_timing() {
+        assert_eq!(
+            replay(&session(), Timing::Original).await,
+            [
+                (None, Duration::ZERO),
+                (Some(30), Duration::from_secs(1)),
+                (Some(48), Duration::from_secs(3)),
+                (Some(46), Duration::from_secs(3)),
+            ]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn replay_accelerated() {
+        assert_eq!(
+            replay(&session(), Timing::Accelerated(2.0)).await,
+            [
+                (None, Duration::ZERO),
+                (Some(30), Duration::from_millis(500)),
+                (Some(48), Duration::from_millis(1500)),
+                (Some(46), Duration::from_millis(1500)),
+            ]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn replay_immediate() {
+        assert_eq!(
+            replay(&session(), Timing::Immediate).await,
+            [
+                (None, Duration::ZERO),
+                (Some(30), Duration::ZERO),
+                (Some(48), Duration::ZERO),
+                (Some(46), Duration::ZERO),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn replay_ends_after_an_error() {
+        let mut bytes = record(&session());
+        bytes.pop();
+
+        let mut replay =
+            ReplayStream::new(Reader::new(bytes.as_slice()).unwrap(), Timing::Immediate);
+        let mut events = Vec::new();
+
+        while let Some(event) = poll_fn(|cx| Pin::new(&mut replay).poll_next(cx)).await {
+            events.push(event);
+        }
+
+        assert_eq!(events.len(), 4);
+        assert!(events[..3].iter().all(Result::is_ok));
+        assert!(matches!(events[3], Err(RecordError::Decode(_))));
+    }
+
+    #[test]
+    fn accelerated_delay_is_clamped() {
+        let bytes = header(VERSION, schema::VERSION);
+        let replay = |factor| {
+            ReplayStream::new(
+                Reader::new(bytes.as_slice()).unwrap(),
+                Timing::Accelerated(factor),
+            )
+        };
+
+        assert_eq!(replay(1e-300).delay(u64::MAX, 0), MAX_DELAY);
+        assert_eq!(replay(f64::MIN_POSITIVE).delay(1, 0), MAX_DELAY);
+        assert_eq!(
+            replay(0.5).delay(3_000_000, 1_000_000),
+            Duration::from_secs(4)
+        );
+        assert_eq!(replay(f64::INFINITY).delay(3_000_000, 0), Duration::ZERO);
+
+        for factor in [0.0, -1.0, f64::NAN] {
+            assert_eq!(replay(factor).delay(3_000_000, 0), Duration::ZERO);
+        }
+    }
+}
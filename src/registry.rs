@@ -0,0 +1,559 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    ffi::{CStr, CString},
+    future::Future,
+    hash::Hash,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+use futures_core::Stream;
+
+use crate::{
+    Capabilities, Device, DeviceCapability, DeviceGroup, Event, Result, Seat,
+    event::{AsRawEvent, DeviceEvent},
+};
+
+/// A live view of the devices known to a libinput context.
+///
+/// The registry consumes the [`DeviceAdded`](crate::event::DeviceAddedEvent) and
+/// [`DeviceRemoved`](crate::event::DeviceRemovedEvent) events of a context and keeps the current set of devices
+/// indexed by sysname, capability, seat and device group. Events can either be fed by hand with
+/// [`handle_event`](Self::handle_event) or by wrapping a stream with [`track`](Self::track).
+///
+/// The registry is a cheap handle, clones share the same state.
+///
+/// # Example usage
+///
+/// ```
+/// use colpetto::{DeviceCapability, DeviceRegistry};
+/// use tokio_stream::StreamExt;
+///
+/// /* libinput initialization is omited for brevity */
+///
+/// let registry = DeviceRegistry::new();
+/// let mut stream = registry.track(libinput.event_stream()?);
+///
+/// tokio::task::spawn_local({
+///     let registry = registry.clone();
+///
+///     async move {
+///         let keyboard = registry
+///             .wait_for(|device| device.has_capability(DeviceCapability::Keyboard))
+///             .await;
+///
+///         keyboard.removed().await;
+///     }
+/// });
+///
+/// while let Some(event) = stream.try_next().await? {
+///     /* handle events as usual */
+/// }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct DeviceRegistry {
+    inner: Rc<RefCell<Inner<Device>>>,
+}
+
+/// The keys a device is indexed by, so that the registry can be tested without libinput devices
+trait Indexed: Clone + Eq {
+    type Seat: Eq + Hash;
+    type Group: Eq + Hash;
+
+    fn sysname(&self) -> &CStr;
+    fn capabilities(&self) -> Capabilities;
+    fn seat(&self) -> Self::Seat;
+    fn group(&self) -> Self::Group;
+}
+
+impl Indexed for Device {
+    type Seat = Seat;
+    type Group = DeviceGroup;
+
+    fn sysname(&self) -> &CStr {
+        Device::sysname(self)
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Device::capabilities(self)
+    }
+
+    fn seat(&self) -> Seat {
+        Device::seat(self)
+    }
+
+    fn group(&self) -> DeviceGroup {
+        Device::device_group(self)
+    }
+}
+
+#[derive(Debug)]
+struct Inner<D: Indexed> {
+    devices: Vec<D>,
+    by_sysname: HashMap<CString, D>,
+    by_capability: HashMap<DeviceCapability, Vec<D>>,
+    by_seat: HashMap<D::Seat, Vec<D>>,
+    by_group: HashMap<D::Group, Vec<D>>,
+    wakers: Vec<Waker>,
+}
+
+impl<D: Indexed> Default for Inner<D> {
+    fn default() -> Self {
+        Self {
+            devices: Vec::new(),
+            by_sysname: HashMap::new(),
+            by_capability: HashMap::new(),
+            by_seat: HashMap::new(),
+            by_group: HashMap::new(),
+            wakers: Vec::new(),
+        }
+    }
+}
+
+impl<D: Indexed> Inner<D> {
+    fn insert(&mut self, device: D) {
+        if self.devices.contains(&device) {
+            return;
+        }
+
+        self.by_sysname
+            .insert(device.sysname().to_owned(), device.clone());
+
+        for capability in device.capabilities() {
+            self.by_capability
+                .entry(capability)
+                .or_default()
+                .push(device.clone());
+        }
+
+        self.by_seat
+            .entry(device.seat())
+            .or_default()
+            .push(device.clone());
+
+        self.by_group
+            .entry(device.group())
+            .or_default()
+            .push(device.clone());
+
+        self.devices.push(device);
+
+        self.wakers.drain(..).for_each(Waker::wake);
+    }
+
+    fn remove(&mut self, device: &D) {
+        self.devices.retain(|d| d != device);
+        self.by_sysname.remove(device.sysname());
+
+        remove_from(&mut self.by_capability, device);
+        remove_from(&mut self.by_seat, device);
+        remove_from(&mut self.by_group, device);
+    }
+}
+
+impl DeviceRegistry {
+    /// Creates a new empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wraps a stream of events, updating the registry with every device event that passes through it.
+    /// Events are forwarded unchanged.
+    pub fn track<S>(&self, stream: S) -> Tracked<S>
+    where
+        S: Stream<Item = Result<Event>> + Unpin,
+    {
+        Tracked {
+            registry: self.clone(),
+            stream,
+        }
+    }
+
+    /// Updates the registry from a single event. Events other than device events are ignored.
+    pub fn handle_event(&self, event: &Event) {
+        match event {
+            Event::Device(DeviceEvent::Added(e)) => self.insert(e.device()),
+            Event::Device(DeviceEvent::Removed(e)) => self.remove(&e.device()),
+            _ => {}
+        }
+    }
+
+    fn insert(&self, device: Device) {
+        self.inner.borrow_mut().insert(device);
+    }
+
+    fn remove(&self, device: &Device) {
+        self.inner.borrow_mut().remove(device);
+    }
+
+    /// Returns all the devices currently present, in the order they were added
+    pub fn devices(&self) -> Vec<Device> {
+        self.inner.borrow().devices.clone()
+    }
+
+    /// Returns the number of devices currently present
+    pub fn len(&self) -> usize {
+        self.inner.borrow().devices.len()
+    }
+
+    /// Returns `true` if no devices are present
+    pub fn is_empty(&self) -> bool {
+        self.inner.borrow().devices.is_empty()
+    }
+
+    /// Returns `true` if the given device is present
+    pub fn contains(&self, device: &Device) -> bool {
+        self.inner.borrow().devices.contains(device)
+    }
+
+    /// Looks up a device by its system name, see [`Device::sysname`]
+    pub fn by_sysname(&self, sysname: &CStr) -> Option<Device> {
+        self.inner.borrow().by_sysname.get(sysname).cloned()
+    }
+
+    /// Returns all the devices with the given capability
+    pub fn with_capability(&self, capability: DeviceCapability) -> Vec<Device> {
+        lookup(&self.inner.borrow().by_capability, &capability)
+    }
+
+    /// Returns all the devices having every capability in `capabilities`, in the order they were added.
+    /// An empty set matches every device.
+    pub fn with_capabilities(&self, capabilities: impl Into<Capabilities>) -> Vec<Device> {
+        let capabilities = capabilities.into();
+
+        self.inner
+            .borrow()
+            .devices
+            .iter()
+            .filter(|device| device.capabilities().contains_all(capabilities))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns all the devices assigned to the given seat
+    pub fn on_seat(&self, seat: &Seat) -> Vec<Device> {
+        lookup(&self.inner.borrow().by_seat, seat)
+    }
+
+    /// Returns all the devices in the given device group
+    pub fn in_group(&self, group: &DeviceGroup) -> Vec<Device> {
+        lookup(&self.inner.borrow().by_group, group)
+    }
+
+    /// Returns a future that resolves to the first device matching `predicate`.
+    ///
+    /// Devices already present are checked first, otherwise the future waits for new devices to be added.
+    /// The registry must keep receiving events for the future to make progress.
+    pub fn wait_for<F>(&self, predicate: F) -> WaitFor<F>
+    where
+        F: Fn(&Device) -> bool,
+    {
+        WaitFor {
+            registry: self.clone(),
+            predicate,
+        }
+    }
+}
+
+fn lookup<K: Eq + Hash, D: Clone>(index: &HashMap<K, Vec<D>>, key: &K) -> Vec<D> {
+    index.get(key).cloned().unwrap_or_default()
+}
+
+fn remove_from<K, D: Eq>(index: &mut HashMap<K, Vec<D>>, device: &D) {
+    index.retain(|_, devices| {
+        devices.retain(|d| d != device);
+        !devices.is_empty()
+    });
+}
+
+/// Stream returned by [`DeviceRegistry::track`]
+#[derive(Debug)]
+pub struct Tracked<S> {
+    registry: DeviceRegistry,
+    stream: S,
+}
+
+impl<S> Tracked<S> {
+    /// Returns the registry updated by this stream
+    pub fn registry(&self) -> &DeviceRegistry {
+        &self.registry
+    }
+
+    /// Consumes the wrapper, returning the underlying stream
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S> Stream for Tracked<S>
+where
+    S: Stream<Item = Result<Event>> + Unpin,
+{
+    type Item = Result<Event>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let item = Pin::new(&mut self.stream).poll_next(cx);
+
+        if let Poll::Ready(Some(Ok(event))) = &item {
+            self.registry.handle_event(event);
+        }
+
+        item
+    }
+}
+
+/// Future returned by [`DeviceRegistry::wait_for`]
+#[must_use = "futures do nothing unless polled"]
+pub struct WaitFor<F> {
+    registry: DeviceRegistry,
+    predicate: F,
+}
+
+impl<F> Future for WaitFor<F>
+where
+    F: Fn(&Device) -> bool + Unpin,
+{
+    type Output = Device;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        poll_wait(&self.registry.inner, &self.predicate, cx)
+    }
+}
+
+/// Resolves to the first device matching `predicate`, or registers the waker to be woken on the next insertion
+fn poll_wait<D: Indexed>(
+    inner: &RefCell<Inner<D>>,
+    predicate: impl Fn(&D) -> bool,
+    cx: &mut Context<'_>,
+) -> Poll<D> {
+    // The predicate may query the registry, so it must run without the state borrowed
+    let candidates = inner.borrow().devices.clone();
+
+    if let Some(device) = candidates.into_iter().find(|d| predicate(d)) {
+        return Poll::Ready(device);
+    }
+
+    let mut inner = inner.borrow_mut();
+
+    if !inner.wakers.iter().any(|w| w.will_wake(cx.waker())) {
+        inner.wakers.push(cx.waker().clone());
+    }
+
+    Poll::Pending
+}
+
+impl<F> std::fmt::Debug for WaitFor<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WaitFor")
+            .field("registry", &self.registry)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        slice,
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+        task::Wake,
+    };
+
+    use super::*;
+
+    use DeviceCapability::*;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct TestDevice {
+        sysname: CString,
+        capabilities: Capabilities,
+        seat: &'static str,
+        group: u32,
+    }
+
+    impl Indexed for TestDevice {
+        type Seat = &'static str;
+        type Group = u32;
+
+        fn sysname(&self) -> &CStr {
+            &self.sysname
+        }
+
+        fn capabilities(&self) -> Capabilities {
+            self.capabilities
+        }
+
+        fn seat(&self) -> &'static str {
+            self.seat
+        }
+
+        fn group(&self) -> u32 {
+            self.group
+        }
+    }
+
+    fn device(sysname: &str, capabilities: Capabilities, group: u32) -> TestDevice {
+        TestDevice {
+            sysname: CString::new(sysname).unwrap(),
+            capabilities,
+            seat: "seat0",
+            group,
+        }
+    }
+
+    #[derive(Default)]
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    impl CountingWaker {
+        fn count(&self) -> usize {
+            self.0.load(Ordering::SeqCst)
+        }
+    }
+
+    #[test]
+    fn add_indexes_devices() {
+        let keyboard = device("event0", Keyboard.into(), 1);
+        let touchpad = device("event1", Pointer | Gesture, 2);
+        let pen = device("event2", TabletTool.into(), 2);
+
+        let mut inner = Inner::default();
+        inner.insert(keyboard.clone());
+        inner.insert(touchpad.clone());
+        inner.insert(pen.clone());
+        inner.insert(keyboard.clone());
+
+        assert_eq!(
+            inner.devices,
+            [keyboard.clone(), touchpad.clone(), pen.clone()]
+        );
+        assert_eq!(inner.by_sysname.get(c"event1"), Some(&touchpad));
+        assert_eq!(
+            lookup(&inner.by_capability, &Keyboard),
+            slice::from_ref(&keyboard)
+        );
+        assert_eq!(
+            lookup(&inner.by_capability, &Gesture),
+            slice::from_ref(&touchpad)
+        );
+        assert_eq!(lookup(&inner.by_capability, &Touch), []);
+        assert_eq!(
+            lookup(&inner.by_seat, &"seat0"),
+            [keyboard, touchpad.clone(), pen.clone()]
+        );
+        assert_eq!(lookup(&inner.by_group, &2), [touchpad, pen]);
+    }
+
+    #[test]
+    fn remove_clears_every_index() {
+        let keyboard = device("event0", Keyboard.into(), 1);
+        let touchpad = device("event1", Pointer | Gesture, 1);
+
+        let mut inner = Inner::default();
+        inner.insert(keyboard.clone());
+        inner.insert(touchpad.clone());
+
+        inner.remove(&touchpad);
+        assert_eq!(inner.devices, slice::from_ref(&keyboard));
+        assert_eq!(inner.by_sysname.get(c"event1"), None);
+        assert!(!inner.by_capability.contains_key(&Pointer));
+        assert!(!inner.by_capability.contains_key(&Gesture));
+        assert_eq!(lookup(&inner.by_group, &1), slice::from_ref(&keyboard));
+
+        // Removing twice or removing an unknown device is harmless
+        inner.remove(&touchpad);
+
+        inner.remove(&keyboard);
+        assert!(inner.devices.is_empty());
+        assert!(inner.by_sysname.is_empty());
+        assert!(inner.by_capability.is_empty());
+        assert!(inner.by_seat.is_empty());
+        assert!(inner.by_group.is_empty());
+    }
+
+    #[test]
+    fn wait_resolves_with_a_present_device() {
+        let keyboard = device("event0", Keyboard.into(), 1);
+
+        let inner = RefCell::new(Inner::default());
+        inner.borrow_mut().insert(keyboard.clone());
+
+        let mut cx = Context::from_waker(Waker::noop());
+        let poll = poll_wait(&inner, |d| d.capabilities.contains(Keyboard), &mut cx);
+
+        assert_eq!(poll, Poll::Ready(keyboard));
+        assert!(inner.borrow().wakers.is_empty());
+    }
+
+    #[test]
+    fn wait_completes_when_a_matching_device_is_added() {
+        let counter = Arc::new(CountingWaker::default());
+        let waker = Waker::from(counter.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        let inner = RefCell::new(Inner::default());
+        let is_keyboard = |d: &TestDevice| d.capabilities.contains(Keyboard);
+
+        assert_eq!(poll_wait(&inner, is_keyboard, &mut cx), Poll::Pending);
+        // Polling again does not register the same waker twice
+        assert_eq!(poll_wait(&inner, is_keyboard, &mut cx), Poll::Pending);
+        assert_eq!(inner.borrow().wakers.len(), 1);
+
+        inner
+            .borrow_mut()
+            .insert(device("event0", Pointer.into(), 1));
+        assert_eq!(counter.count(), 1);
+        assert_eq!(poll_wait(&inner, is_keyboard, &mut cx), Poll::Pending);
+
+        let keyboard = device("event1", Keyboard.into(), 2);
+        inner.borrow_mut().insert(keyboard.clone());
+        assert_eq!(counter.count(), 2);
+        assert_eq!(
+            poll_wait(&inner, is_keyboard, &mut cx),
+            Poll::Ready(keyboard)
+        );
+    }
+
+    #[test]
+    fn wait_stays_pending_after_removal() {
+        let keyboard = device("event0", Keyboard.into(), 1);
+
+        let inner = RefCell::new(Inner::default());
+        inner.borrow_mut().insert(keyboard.clone());
+        inner.borrow_mut().remove(&keyboard);
+
+        let mut cx = Context::from_waker(Waker::noop());
+        assert_eq!(
+            poll_wait(&inner, |d| *d == keyboard, &mut cx),
+            Poll::Pending
+        );
+    }
+
+    #[test]
+    fn tracked_forwards_items_unchanged() {
+        let registry = DeviceRegistry::new();
+        let mut tracked = registry.track(tokio_stream::iter([Err(crate::Error::Resume)]));
+        let mut cx = Context::from_waker(Waker::noop());
+
+        assert!(matches!(
+            Pin::new(&mut tracked).poll_next(&mut cx),
+            Poll::Ready(Some(Err(crate::Error::Resume)))
+        ));
+        assert!(matches!(
+            Pin::new(&mut tracked).poll_next(&mut cx),
+            Poll::Ready(None)
+        ));
+
+        assert!(tracked.registry().is_empty());
+        assert!(Rc::ptr_eq(&tracked.registry().inner, &registry.inner));
+    }
+}
@@ -0,0 +1,300 @@
+//! Serialization support, enabled by the `serde` feature.
+//!
+//! Owned events, [`DeviceInfo`](crate::DeviceInfo), [`DeviceConfig`](crate::DeviceConfig),
+//! [`DeviceProperties`](crate::DeviceProperties), [`DeviceId`] and the types they are made of
+//! implement [`Serialize`] and [`Deserialize`].
+//! Their serialized form is part of the schema identified by [`VERSION`]:
+//!
+//! - variants and fields are serialized with their Rust names, the names never change within a version
+//! - new optional fields may be added without a version bump, data written before they existed still deserializes
+//! - any other change, e.g. renaming or removing something, bumps the version
+//!
+//! [`DeviceId`] is serialized as its [stable string form](DeviceId), so it can be used as a map key,
+//! and [`Capabilities`] as a list of capabilities.
+//!
+//! Data meant to outlive the process, e.g. saved settings, should be wrapped in [`Versioned`],
+//! which records the version it was written with and refuses data written by a newer colpetto.
+//!
+//! # Example usage
+//!
+//! ```
+//! use std::collections::HashMap;
+//!
+//! use colpetto::{DeviceConfig, DeviceId, schema::Versioned};
+//!
+//! let mut settings = HashMap::new();
+//! settings.insert(device.device_id(), device.current_config());
+//!
+//! let saved = serde_json::to_string(&Versioned::new(settings))?;
+//!
+//! let settings: HashMap<DeviceId, DeviceConfig> =
+//!     serde_json::from_str::<Versioned<_>>(&saved)?.into_inner();
+//! ```
+
+use std::{fmt, marker::PhantomData};
+
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{self, MapAccess, SeqAccess, Visitor},
+    ser::SerializeStruct,
+};
+
+use crate::{Capabilities, DeviceCapability, DeviceId};
+
+/// The current version of the schema
+pub const VERSION: u32 = 1;
+
+/// A value tagged with the schema version it was serialized with.
+///
+/// Serialized as a struct with a `version` field followed by a `data` field.
+/// Deserialization fails if the version is newer than [`VERSION`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Versioned<T> {
+    version: u32,
+    data: T,
+}
+
+impl<T> Versioned<T> {
+    /// Tags `data` with the current version
+    pub const fn new(data: T) -> Self {
+        Self {
+            version: VERSION,
+            data,
+        }
+    }
+
+    /// The version the data was serialized with
+    pub const fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Returns a reference to the data
+    pub const fn data(&self) -> &T {
+        &self.data
+    }
+
+    /// Returns the data
+    pub fn into_inner(self) -> T {
+        self.data
+    }
+}
+
+impl<T: Serialize> Serialize for Versioned<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Versioned", 2)?;
+        state.serialize_field("version", &self.version)?;
+        state.serialize_field("data", &self.data)?;
+        state.end()
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Versioned<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_struct(
+            "Versioned",
+            &["version", "data"],
+            VersionedVisitor(PhantomData),
+        )
+    }
+}
+
+struct VersionedVisitor<T>(PhantomData<T>);
+
+/// Checks the version before the data is read, so newer data fails with a meaningful error
+fn check_version<E: de::Error>(version: u32) -> Result<u32, E> {
+    if version > VERSION {
+        return Err(E::custom(format_args!(
+            "schema version {version} is newer than the supported version {VERSION}"
+        )));
+    }
+
+    Ok(version)
+}
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for VersionedVisitor<T> {
+    type Value = Versioned<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("versioned colpetto data")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let version = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let version = check_version(version)?;
+
+        let data = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+
+        Ok(Versioned { version, data })
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut version = None;
+        let mut data = None;
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "version" if version.is_none() => {
+                    version = Some(check_version(map.next_value()?)?);
+                }
+                // Serialized after the version, which is checked first unless the fields were reordered
+                "data" if data.is_none() => data = Some(map.next_value()?),
+                "version" | "data" => {
+                    return Err(de::Error::custom(format_args!("duplicate field `{key}`")));
+                }
+                _ => {
+                    map.next_value::<de::IgnoredAny>()?;
+                }
+            }
+        }
+
+        Ok(Versioned {
+            version: version.ok_or_else(|| de::Error::missing_field("version"))?,
+            data: data.ok_or_else(|| de::Error::missing_field("data"))?,
+        })
+    }
+}
+
+impl Serialize for DeviceId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for DeviceId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        id.parse().map_err(de::Error::custom)
+    }
+}
+
+impl Serialize for Capabilities {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for Capabilities {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Vec::<DeviceCapability>::deserialize(deserializer)?
+            .into_iter()
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::DeviceConfig;
+
+    fn parse(json: &str) -> serde_json::Result<Versioned<DeviceConfig>> {
+        serde_json::from_str(json)
+    }
+
+    fn config() -> DeviceConfig {
+        DeviceConfig {
+            left_handed: Some(true),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let versioned = Versioned::new(config());
+        let json = serde_json::to_string(&versioned).unwrap();
+
+        assert!(json.starts_with(&format!(r#"{{"version":{VERSION},"data":"#)));
+        assert_eq!(parse(&json).unwrap(), versioned);
+        assert_eq!(parse(&json).unwrap().into_inner(), config());
+    }
+
+    #[test]
+    fn round_trip_as_sequence() {
+        let json = format!(r#"[{VERSION},{{"left_handed":true}}]"#);
+        assert_eq!(parse(&json).unwrap(), Versioned::new(config()));
+
+        assert!(parse("[]").is_err());
+        assert!(parse(&format!("[{VERSION}]")).is_err());
+    }
+
+    #[test]
+    fn older_versions_are_accepted() {
+        let versioned = parse(r#"{"version":0,"data":{}}"#).unwrap();
+        assert_eq!(versioned.version(), 0);
+        assert_eq!(versioned.data(), &DeviceConfig::default());
+    }
+
+    #[test]
+    fn newer_versions_are_rejected() {
+        let newer = VERSION + 1;
+
+        for json in [
+            format!(r#"{{"version":{newer},"data":{{}}}}"#),
+            format!(r#"[{newer},{{}}]"#),
+            // The version is checked before the data, which the newer schema may have changed
+            format!(r#"{{"version":{newer},"data":"not a config"}}"#),
+        ] {
+            let error = parse(&json).unwrap_err().to_string();
+            assert!(
+                error.contains(&format!("schema version {newer} is newer")),
+                "{json}: {error}"
+            );
+        }
+    }
+
+    #[test]
+    fn duplicate_fields_are_rejected() {
+        let error = parse(r#"{"version":1,"version":1,"data":{}}"#)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("duplicate field `version`"), "{error}");
+
+        let error = parse(r#"{"version":1,"data":{},"data":{}}"#)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("duplicate field `data`"), "{error}");
+    }
+
+    #[test]
+    fn missing_fields_are_rejected() {
+        let error = parse(r#"{"data":{}}"#).unwrap_err().to_string();
+        assert!(error.contains("missing field `version`"), "{error}");
+
+        let error = parse(r#"{"version":1}"#).unwrap_err().to_string();
+        assert!(error.contains("missing field `data`"), "{error}");
+    }
+
+    #[test]
+    fn unknown_fields_are_ignored() {
+        let versioned = parse(r#"{"comment":"saved","version":1,"data":{}}"#).unwrap();
+        assert_eq!(versioned.into_inner(), DeviceConfig::default());
+    }
+
+    #[test]
+    fn device_properties_round_trip() {
+        let properties = crate::DeviceProperties::from_properties([
+            ("DEVNAME", "/dev/input/event5"),
+            ("ID_INPUT", "1"),
+            ("ID_INPUT_TOUCHSCREEN", "1"),
+            ("MOUSE_DPI", "400 *800@125"),
+            ("LIBINPUT_CALIBRATION_MATRIX", "0 -1 1 1 0 0"),
+            ("PRODUCT", "3/46d/c52b/111"),
+        ]);
+
+        let json = serde_json::to_string(&properties).unwrap();
+        assert_eq!(
+            serde_json::from_str::<crate::DeviceProperties>(&json).unwrap(),
+            properties
+        );
+
+        // Missing fields take their default, so newer optional fields can be added
+        assert_eq!(
+            serde_json::from_str::<crate::DeviceProperties>("{}").unwrap(),
+            crate::DeviceProperties::default()
+        );
+    }
+}
@@ -1,8 +1,14 @@
-use crate::{macros, sys};
+use std::{
+    ffi::CStr,
+    hash::{Hash, Hasher},
+};
+
+use crate::sys;
 
 /// The base handle for accessing libinput seats
+///
+/// Two handles compare equal if they refer to the same libinput seat.
 pub struct Seat {
-    #[allow(unused)]
     raw: *mut sys::libinput_seat,
 }
 
@@ -19,6 +25,27 @@ impl Seat {
             }
         }
     }
+
+    /// Returns the raw underlying pointer
+    pub fn as_raw(&self) -> *mut sys::libinput_seat {
+        self.raw
+    }
+
+    /// Return the physical name of the seat.
+    /// For libinput contexts created from udev, this is always the same value as passed into
+    /// [`udev_assign_seat`](crate::Libinput::udev_assign_seat) and all seats from that context will have the same physical name.
+    ///
+    /// The physical name of the seat is one that is usually set by the system or lower levels of the stack.
+    /// In most cases, this is the base filter for devices - devices assigned to seats outside the current seat will not be available to the caller.
+    pub fn physical_name(&self) -> &CStr {
+        unsafe { CStr::from_ptr(sys::libinput_seat_get_physical_name(self.raw)) }
+    }
+
+    /// Return the logical name of the seat.
+    /// This is an identifier to group sets of devices within the compositor.
+    pub fn logical_name(&self) -> &CStr {
+        unsafe { CStr::from_ptr(sys::libinput_seat_get_logical_name(self.raw)) }
+    }
 }
 
 impl Drop for Seat {
@@ -35,4 +62,44 @@ impl Clone for Seat {
     }
 }
 
-macros::impl_debug!(Seat);
+impl PartialEq for Seat {
+    fn eq(&self, other: &Self) -> bool {
+        self.raw == other.raw
+    }
+}
+
+impl Eq for Seat {}
+
+impl Hash for Seat {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.raw.hash(state);
+    }
+}
+
+impl std::fmt::Debug for Seat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let alternate = f.alternate();
+        let mut debug = f.debug_struct("Seat");
+
+        if alternate {
+            debug.field("raw", &self.raw);
+        }
+
+        debug
+            .field("physical_name", &self.physical_name())
+            .field("logical_name", &self.logical_name())
+            .finish()
+    }
+}
+
+/// Formats as `physical logical`, e.g. `seat0 default`
+impl std::fmt::Display for Seat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.physical_name().to_string_lossy(),
+            self.logical_name().to_string_lossy()
+        )
+    }
+}
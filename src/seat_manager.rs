@@ -0,0 +1,186 @@
+use std::{
+    ffi::CStr,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+use futures_core::Stream;
+
+use crate::{Error, Event, EventStream, Libinput, Result};
+
+/// Runs one libinput context per seat and merges their events into a single stream.
+///
+/// A context can only be assigned to a single seat, serving several seats requires several contexts.
+/// The manager creates them with the given factory, assigns them to their seat and yields their events
+/// tagged with the seat they come from. Seats can be added and removed at any time, and suspended and
+/// resumed independently, e.g. when only one seat's session becomes inactive.
+///
+/// # Example usage
+///
+/// ```
+/// use colpetto::{Libinput, SeatManager};
+/// use tokio_stream::StreamExt;
+///
+/// let mut manager = SeatManager::new(|_seat| Libinput::with_interface(Interface, None));
+/// manager.add_seat(c"seat0")?;
+/// manager.add_seat(c"seat1")?;
+///
+/// while let Some(event) = manager.try_next().await? {
+///     println!("{:?}: {}", event.seat, event.event.event_type());
+/// }
+/// ```
+pub struct SeatManager {
+    factory: Factory,
+    seats: Vec<ManagedSeat>,
+    /// Index of the seat polled first, rotated so a busy seat cannot starve the others
+    next: usize,
+    waker: Option<Waker>,
+}
+
+/// Creates the context of a seat, see [`SeatManager::new`]
+type Factory = Box<dyn FnMut(&CStr) -> Result<Libinput>>;
+
+struct ManagedSeat {
+    name: Rc<CStr>,
+    libinput: Libinput,
+    stream: EventStream,
+}
+
+/// An event tagged with the seat it comes from, yielded by [`SeatManager`]
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct SeatEvent {
+    /// The name of the seat, as passed to [`SeatManager::add_seat`]
+    pub seat: Rc<CStr>,
+    /// The event
+    pub event: Event,
+}
+
+impl SeatManager {
+    /// Creates a manager without seats, `factory` is called to create the context of every seat added.
+    /// The contexts must not be assigned to a seat yet.
+    pub fn new<F>(factory: F) -> Self
+    where
+        F: FnMut(&CStr) -> Result<Libinput> + 'static,
+    {
+        Self {
+            factory: Box::new(factory),
+            seats: Vec::new(),
+            next: 0,
+            waker: None,
+        }
+    }
+
+    /// Creates a context for `seat` and starts streaming its events.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio context
+    ///
+    /// # Errors
+    /// Fails if the seat is already managed, or if the context cannot be created or assigned to the seat.
+    pub fn add_seat(&mut self, seat: &CStr) -> Result<()> {
+        if self.position(seat).is_some() {
+            return Err(Error::DuplicateSeat(seat.to_owned()));
+        }
+
+        let mut libinput = (self.factory)(seat)?;
+        libinput.udev_assign_seat(seat)?;
+
+        let stream = libinput.event_stream()?;
+
+        self.seats.push(ManagedSeat {
+            name: seat.into(),
+            libinput,
+            stream,
+        });
+
+        // The new stream must be polled to register its fd
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+
+        Ok(())
+    }
+
+    /// Stops managing `seat`, destroying its context.
+    /// Events of the seat not yet yielded are dropped.
+    pub fn remove_seat(&mut self, seat: &CStr) -> Result<()> {
+        let index = self.position(seat).ok_or_else(|| unknown(seat))?;
+
+        self.seats.remove(index);
+
+        Ok(())
+    }
+
+    /// Suspends the context of `seat`, see [`Libinput::suspend`]
+    pub fn suspend_seat(&self, seat: &CStr) -> Result<()> {
+        self.libinput(seat).ok_or_else(|| unknown(seat))?.suspend();
+
+        Ok(())
+    }
+
+    /// Resumes the context of `seat`, see [`Libinput::resume`]
+    pub fn resume_seat(&self, seat: &CStr) -> Result<()> {
+        self.libinput(seat).ok_or_else(|| unknown(seat))?.resume()
+    }
+
+    /// Returns the context of `seat`, if managed
+    pub fn libinput(&self, seat: &CStr) -> Option<&Libinput> {
+        self.position(seat).map(|index| &self.seats[index].libinput)
+    }
+
+    /// Returns the names of the managed seats, in the order they were added
+    pub fn seats(&self) -> impl Iterator<Item = &CStr> {
+        self.seats.iter().map(|seat| &*seat.name)
+    }
+
+    fn position(&self, seat: &CStr) -> Option<usize> {
+        self.seats.iter().position(|managed| *managed.name == *seat)
+    }
+}
+
+fn unknown(seat: &CStr) -> Error {
+    Error::UnknownSeat(seat.to_owned())
+}
+
+impl Stream for SeatManager {
+    type Item = Result<SeatEvent>;
+
+    /// Never ends, even when no seats are managed, since seats may be added later
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let len = this.seats.len();
+
+        for offset in 0..len {
+            let index = (this.next + offset) % len;
+            let seat = &mut this.seats[index];
+
+            match Pin::new(&mut seat.stream).poll_next(cx) {
+                Poll::Ready(Some(item)) => {
+                    this.next = (index + 1) % len;
+
+                    return Poll::Ready(Some(item.map(|event| SeatEvent {
+                        seat: seat.name.clone(),
+                        event,
+                    })));
+                }
+                // Event streams never end
+                Poll::Ready(None) | Poll::Pending => {}
+            }
+        }
+
+        this.waker = Some(cx.waker().clone());
+
+        Poll::Pending
+    }
+}
+
+impl std::fmt::Debug for SeatManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SeatManager")
+            .field("seats", &self.seats().collect::<Vec<_>>())
+            .finish_non_exhaustive()
+    }
+}
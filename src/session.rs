@@ -0,0 +1,385 @@
+//! Opening devices through a session manager.
+//!
+//! An unprivileged compositor cannot open `/dev/input` devices itself, it asks the session manager
+//! (logind's `TakeDevice`, or seatd) to open them instead. The session manager also pauses the session
+//! when the user switches to another VT, revoking access to the devices, and resumes it when they come back.
+//!
+//! A [`SessionOpener`] is a backend talking to a session manager. Wrapped in a [`Session`], it provides the
+//! open interface of a context and translates pause and resume signals into [`Libinput::suspend`] and [`Libinput::resume`].
+//!
+//! Sessions may start inactive, in which case the devices fail to open when the seat is assigned.
+//! They are opened once the session manager sends the first [`SessionEvent::Resumed`].
+//!
+//! Available backends are:
+//! - [`LibseatSession`], using libseat, which supports both logind and seatd. Requires the `libseat` feature.
+//! - [`MockSession`], opening devices directly and pausing on demand, for testing without a session manager.
+//!
+//! # Example usage
+//!
+//! ```
+//! use colpetto::session::{LibseatSession, Session, SessionEvent};
+//!
+//! let session = Session::new(LibseatSession::open()?);
+//! let mut libinput = session.libinput(None)?;
+//! libinput.udev_assign_seat(c"seat0")?;
+//!
+//! loop {
+//!     /* wait for either the session or the libinput fd to be readable */
+//!
+//!     for event in session.dispatch(&libinput)? {
+//!         match event {
+//!             SessionEvent::Paused => println!("vt switched away"),
+//!             SessionEvent::Resumed => println!("vt switched back"),
+//!         }
+//!     }
+//!
+//!     libinput.dispatch()?;
+//! }
+//! ```
+
+use std::{
+    cell::{Cell, RefCell},
+    io,
+    os::fd::{BorrowedFd, OwnedFd},
+    path::Path,
+    rc::Rc,
+};
+
+use crate::{Libinput, Logger, OFlags, OpenInterface, Result};
+
+#[cfg(feature = "libseat")]
+mod libseat;
+mod mock;
+
+#[cfg(feature = "libseat")]
+pub use libseat::LibseatSession;
+pub use mock::MockSession;
+
+/// A change of the session state, returned by [`Session::dispatch`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SessionEvent {
+    /// The session became inactive, e.g. the user switched to another VT.
+    /// Devices are closed and the context is suspended.
+    Paused,
+    /// The session became active again, the context is resumed and devices are reopened.
+    Resumed,
+}
+
+/// A backend opening devices through a session manager
+pub trait SessionOpener {
+    /// Opens the device at `path`. Backends may ignore `flags` if the session manager chooses them.
+    fn open_device(&mut self, path: &Path, flags: OFlags) -> io::Result<OwnedFd>;
+
+    /// Closes a device previously opened with [`open_device`](Self::open_device)
+    fn close_device(&mut self, fd: OwnedFd);
+
+    /// Processes the pending messages from the session manager, returning the resulting state changes in order
+    fn dispatch(&mut self) -> io::Result<Vec<SessionEvent>>;
+
+    /// Called with each event returned by [`dispatch`](Self::dispatch), right before the context reacts to it,
+    /// for backends tracking the session state one event at a time
+    fn deliver(&mut self, _event: SessionEvent) {}
+
+    /// Called once the devices have been closed after a [`SessionEvent::Paused`],
+    /// for session managers that wait for the client to release the session
+    fn acknowledge_pause(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Returns `true` if the session is currently active
+    fn is_active(&self) -> bool;
+
+    /// A file descriptor becoming readable when the session manager has messages to dispatch, if the backend has one
+    fn poll_fd(&self) -> Option<BorrowedFd<'_>> {
+        None
+    }
+}
+
+/// Ties a [`SessionOpener`] to a libinput context.
+///
+/// The session is a cheap handle, clones share the same backend.
+pub struct Session<S> {
+    opener: Rc<RefCell<S>>,
+    /// Whether the context was suspended by a [`SessionEvent::Paused`] and not resumed since
+    suspended: Rc<Cell<bool>>,
+}
+
+/// The part of a context driven by [`Session::dispatch`]
+trait Suspend {
+    fn suspend(&self);
+    fn resume(&self) -> Result<()>;
+}
+
+impl Suspend for Libinput {
+    fn suspend(&self) {
+        Libinput::suspend(self)
+    }
+
+    fn resume(&self) -> Result<()> {
+        Libinput::resume(self)
+    }
+}
+
+impl<S: SessionOpener + 'static> Session<S> {
+    /// Wraps a session backend
+    pub fn new(opener: S) -> Self {
+        Self {
+            opener: Rc::new(RefCell::new(opener)),
+            suspended: Rc::default(),
+        }
+    }
+
+    /// Returns an open interface opening devices through this session,
+    /// to be passed to [`Libinput::with_interface`]
+    pub fn interface(&self) -> SessionInterface<S> {
+        SessionInterface {
+            opener: self.opener.clone(),
+        }
+    }
+
+    /// Creates a new udev context opening devices through this session
+    pub fn libinput(&self, logger: Option<Logger>) -> Result<Libinput> {
+        Libinput::with_interface(self.interface(), logger)
+    }
+
+    /// Processes the pending messages from the session manager, suspending `libinput` when the session is paused
+    /// and resuming it when the session is resumed. Returns the state changes in order.
+    pub fn dispatch(&self, libinput: &Libinput) -> Result<Vec<SessionEvent>> {
+        self.dispatch_to(libinput)
+    }
+
+    fn dispatch_to(&self, context: &impl Suspend) -> Result<Vec<SessionEvent>> {
+        // The borrow must end before suspending or resuming, which opens and closes devices through the interface
+        let events = self.opener.borrow_mut().dispatch()?;
+
+        for event in &events {
+            self.opener.borrow_mut().deliver(*event);
+
+            match event {
+                SessionEvent::Paused => {
+                    context.suspend();
+                    self.suspended.set(true);
+                    self.opener.borrow_mut().acknowledge_pause()?;
+                }
+                SessionEvent::Resumed => {
+                    // Resuming a context that was never suspended does nothing, so the devices that failed
+                    // to open while the session was inactive would never be retried
+                    if !self.suspended.get() {
+                        context.suspend();
+                    }
+
+                    self.suspended.set(false);
+                    context.resume()?;
+                }
+            }
+        }
+
+        Ok(events)
+    }
+
+    /// Returns `true` if the session is currently active
+    pub fn is_active(&self) -> bool {
+        self.opener.borrow().is_active()
+    }
+
+    /// Calls `f` with the backend, e.g. to switch VT with [`LibseatSession::switch_session`]
+    pub fn with_opener<R>(&self, f: impl FnOnce(&mut S) -> R) -> R {
+        f(&mut self.opener.borrow_mut())
+    }
+}
+
+impl<S> Clone for Session<S> {
+    fn clone(&self) -> Self {
+        Self {
+            opener: self.opener.clone(),
+            suspended: self.suspended.clone(),
+        }
+    }
+}
+
+impl<S> std::fmt::Debug for Session<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Session").finish_non_exhaustive()
+    }
+}
+
+/// The open interface of a [`Session`], see [`Session::interface`]
+pub struct SessionInterface<S> {
+    opener: Rc<RefCell<S>>,
+}
+
+impl<S: SessionOpener> OpenInterface for SessionInterface<S> {
+    fn open(&mut self, path: &Path, flags: OFlags) -> io::Result<OwnedFd> {
+        self.opener.borrow_mut().open_device(path, flags)
+    }
+
+    fn close(&mut self, fd: OwnedFd) {
+        self.opener.borrow_mut().close_device(fd)
+    }
+}
+
+impl<S> std::fmt::Debug for SessionInterface<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionInterface").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records the calls made by [`Session::dispatch`] instead of driving a real context
+    #[derive(Default)]
+    struct Context {
+        calls: RefCell<Vec<&'static str>>,
+    }
+
+    impl Suspend for Context {
+        fn suspend(&self) {
+            self.calls.borrow_mut().push("suspend");
+        }
+
+        fn resume(&self) -> Result<()> {
+            self.calls.borrow_mut().push("resume");
+            Ok(())
+        }
+    }
+
+    impl Context {
+        fn take_calls(&self) -> Vec<&'static str> {
+            self.calls.take()
+        }
+    }
+
+    #[test]
+    fn pause_and_resume() {
+        let mock = MockSession::new();
+        let session = Session::new(mock.clone());
+        let context = Context::default();
+
+        mock.pause();
+        assert_eq!(
+            session.dispatch_to(&context).unwrap(),
+            [SessionEvent::Paused]
+        );
+        assert_eq!(context.take_calls(), ["suspend"]);
+        assert_eq!(mock.acknowledged_pauses(), 1);
+        assert!(!session.is_active());
+
+        mock.resume();
+        assert_eq!(
+            session.dispatch_to(&context).unwrap(),
+            [SessionEvent::Resumed]
+        );
+        assert_eq!(context.take_calls(), ["resume"]);
+        assert!(session.is_active());
+    }
+
+    #[test]
+    fn first_resume_reopens_devices() {
+        let mock = MockSession::new();
+        let session = Session::new(mock.clone());
+        let context = Context::default();
+
+        // A session starting inactive sends a resume without a prior pause
+        mock.resume();
+        assert_eq!(
+            session.dispatch_to(&context).unwrap(),
+            [SessionEvent::Resumed]
+        );
+        assert_eq!(context.take_calls(), ["suspend", "resume"]);
+
+        mock.pause();
+        mock.resume();
+        assert_eq!(
+            session.dispatch_to(&context).unwrap(),
+            [SessionEvent::Paused, SessionEvent::Resumed]
+        );
+        assert_eq!(context.take_calls(), ["suspend", "resume"]);
+        assert_eq!(mock.acknowledged_pauses(), 1);
+    }
+
+    #[test]
+    fn nothing_pending() {
+        let session = Session::new(MockSession::new());
+        let context = Context::default();
+
+        assert_eq!(session.dispatch_to(&context).unwrap(), []);
+        assert_eq!(context.take_calls(), Vec::<&str>::new());
+        assert!(session.is_active());
+    }
+
+    /// Opens a device on resume like libinput does, recording whether it succeeded
+    struct Reopening {
+        interface: RefCell<SessionInterface<MockSession>>,
+        opened: RefCell<Vec<bool>>,
+    }
+
+    impl Suspend for Reopening {
+        fn suspend(&self) {}
+
+        fn resume(&self) -> Result<()> {
+            let mut interface = self.interface.borrow_mut();
+            let opened = interface.open(Path::new("/dev/null"), OFlags::RDONLY);
+
+            self.opened.borrow_mut().push(opened.is_ok());
+            opened.map(|fd| interface.close(fd)).ok();
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn queued_events_update_the_state_one_at_a_time() {
+        let mock = MockSession::new();
+        let session = Session::new(mock.clone());
+        let context = Reopening {
+            interface: RefCell::new(session.interface()),
+            opened: RefCell::default(),
+        };
+
+        mock.resume();
+        mock.pause();
+        assert_eq!(
+            session.dispatch_to(&context).unwrap(),
+            [SessionEvent::Resumed, SessionEvent::Paused]
+        );
+        assert_eq!(context.opened.take(), [true]);
+        assert!(!session.is_active());
+
+        mock.pause();
+        mock.resume();
+        assert_eq!(
+            session.dispatch_to(&context).unwrap(),
+            [SessionEvent::Paused, SessionEvent::Resumed]
+        );
+        assert_eq!(context.opened.take(), [true]);
+        assert!(session.is_active());
+    }
+
+    #[test]
+    fn paused_session_refuses_to_open() {
+        let mock = MockSession::new();
+        let session = Session::new(mock.clone());
+        let mut interface = session.interface();
+        let context = Context::default();
+        let path = Path::new("/dev/null");
+
+        let fd = interface.open(path, OFlags::RDONLY).unwrap();
+        assert_eq!(mock.open_devices(), [path]);
+        interface.close(fd);
+        assert!(mock.open_devices().is_empty());
+
+        mock.pause();
+        session.dispatch_to(&context).unwrap();
+        let error = interface.open(path, OFlags::RDONLY).unwrap_err();
+        assert_eq!(
+            error.raw_os_error(),
+            Some(rustix::io::Errno::PERM.raw_os_error())
+        );
+
+        mock.resume();
+        session.dispatch_to(&context).unwrap();
+        assert!(interface.open(path, OFlags::RDONLY).is_ok());
+    }
+}
@@ -0,0 +1,204 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    ffi::{CStr, CString, c_int, c_void},
+    io,
+    os::{
+        fd::{AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd},
+        unix::ffi::OsStrExt,
+    },
+    path::Path,
+    ptr::NonNull,
+};
+
+use crate::{
+    OFlags,
+    session::{SessionEvent, SessionOpener},
+};
+
+#[allow(non_camel_case_types)]
+mod ffi {
+    use std::ffi::{c_char, c_int, c_void};
+
+    #[repr(C)]
+    pub struct libseat {
+        _private: [u8; 0],
+    }
+
+    #[repr(C)]
+    pub struct libseat_seat_listener {
+        pub enable_seat: Option<unsafe extern "C" fn(seat: *mut libseat, userdata: *mut c_void)>,
+        pub disable_seat: Option<unsafe extern "C" fn(seat: *mut libseat, userdata: *mut c_void)>,
+    }
+
+    #[link(name = "seat")]
+    unsafe extern "C" {
+        pub fn libseat_open_seat(
+            listener: *const libseat_seat_listener,
+            userdata: *mut c_void,
+        ) -> *mut libseat;
+        pub fn libseat_disable_seat(seat: *mut libseat) -> c_int;
+        pub fn libseat_close_seat(seat: *mut libseat) -> c_int;
+        pub fn libseat_open_device(
+            seat: *mut libseat,
+            path: *const c_char,
+            fd: *mut c_int,
+        ) -> c_int;
+        pub fn libseat_close_device(seat: *mut libseat, device_id: c_int) -> c_int;
+        pub fn libseat_seat_name(seat: *mut libseat) -> *const c_char;
+        pub fn libseat_switch_session(seat: *mut libseat, session: c_int) -> c_int;
+        pub fn libseat_get_fd(seat: *mut libseat) -> c_int;
+        pub fn libseat_dispatch(seat: *mut libseat, timeout: c_int) -> c_int;
+    }
+}
+
+static LISTENER: ffi::libseat_seat_listener = ffi::libseat_seat_listener {
+    enable_seat: Some(enable_seat),
+    disable_seat: Some(disable_seat),
+};
+
+unsafe extern "C" fn enable_seat(_seat: *mut ffi::libseat, userdata: *mut c_void) {
+    let state = unsafe { &*(userdata as *const State) }; // Safe because we manage the userdata pointer
+
+    state.active.set(true);
+    state.pending.borrow_mut().push(SessionEvent::Resumed);
+}
+
+unsafe extern "C" fn disable_seat(_seat: *mut ffi::libseat, userdata: *mut c_void) {
+    let state = unsafe { &*(userdata as *const State) }; // Safe because we manage the userdata pointer
+
+    state.active.set(false);
+    state.pending.borrow_mut().push(SessionEvent::Paused);
+}
+
+/// Filled by the libseat callbacks, boxed so its address stays stable
+#[derive(Default)]
+struct State {
+    active: Cell<bool>,
+    pending: RefCell<Vec<SessionEvent>>,
+}
+
+/// A session backend using [libseat](https://git.sr.ht/~kennylevinsen/seatd), which talks to either logind or seatd.
+///
+/// The seat starts disabled, devices can only be opened once a [`SessionEvent::Resumed`] has been dispatched.
+/// libseat chooses the flags devices are opened with, the requested ones are ignored.
+pub struct LibseatSession {
+    raw: NonNull<ffi::libseat>,
+    // Only dropped after the seat is closed
+    state: Box<State>,
+    /// Maps the fds handed out to their libseat device ids
+    devices: HashMap<RawFd, c_int>,
+}
+
+impl LibseatSession {
+    /// Opens the seat of the current session
+    pub fn open() -> io::Result<Self> {
+        let state = Box::new(State::default());
+
+        let raw =
+            unsafe { ffi::libseat_open_seat(&LISTENER, &*state as *const State as *mut c_void) };
+
+        let raw = NonNull::new(raw).ok_or_else(io::Error::last_os_error)?;
+
+        Ok(Self {
+            raw,
+            state,
+            devices: HashMap::new(),
+        })
+    }
+
+    /// Returns the raw underlying pointer
+    pub fn as_raw(&self) -> *mut c_void {
+        self.raw.as_ptr().cast()
+    }
+
+    /// Returns the name of the seat, e.g. `seat0`
+    pub fn seat_name(&self) -> &CStr {
+        unsafe { CStr::from_ptr(ffi::libseat_seat_name(self.raw.as_ptr())) }
+    }
+
+    /// Asks the session manager to switch to another session, usually the VT with the given number
+    pub fn switch_session(&mut self, session: i32) -> io::Result<()> {
+        match unsafe { ffi::libseat_switch_session(self.raw.as_ptr(), session) } {
+            0 => Ok(()),
+            _ => Err(io::Error::last_os_error()),
+        }
+    }
+}
+
+impl SessionOpener for LibseatSession {
+    fn open_device(&mut self, path: &Path, _flags: OFlags) -> io::Result<OwnedFd> {
+        let path = CString::new(path.as_os_str().as_bytes())?;
+        let mut fd = -1;
+
+        let id = unsafe { ffi::libseat_open_device(self.raw.as_ptr(), path.as_ptr(), &mut fd) };
+
+        if id < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        self.devices.insert(fd, id);
+
+        // Safety: libseat hands over ownership of the fd, it is closed by the caller after `libseat_close_device`
+        Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+    }
+
+    fn close_device(&mut self, fd: OwnedFd) {
+        if let Some(id) = self.devices.remove(&fd.as_raw_fd()) {
+            unsafe { ffi::libseat_close_device(self.raw.as_ptr(), id) };
+        }
+    }
+
+    fn dispatch(&mut self) -> io::Result<Vec<SessionEvent>> {
+        if unsafe { ffi::libseat_dispatch(self.raw.as_ptr(), 0) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(std::mem::take(&mut *self.state.pending.borrow_mut()))
+    }
+
+    fn acknowledge_pause(&mut self) -> io::Result<()> {
+        match unsafe { ffi::libseat_disable_seat(self.raw.as_ptr()) } {
+            0 => Ok(()),
+            _ => Err(io::Error::last_os_error()),
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        self.state.active.get()
+    }
+
+    fn poll_fd(&self) -> Option<BorrowedFd<'_>> {
+        let fd = unsafe { ffi::libseat_get_fd(self.raw.as_ptr()) };
+
+        // Safety: the fd is owned by the seat, which outlives the borrow
+        (fd >= 0).then(|| unsafe { BorrowedFd::borrow_raw(fd) })
+    }
+}
+
+impl Drop for LibseatSession {
+    fn drop(&mut self) {
+        for (_, id) in self.devices.drain() {
+            unsafe { ffi::libseat_close_device(self.raw.as_ptr(), id) };
+        }
+
+        unsafe { ffi::libseat_close_seat(self.raw.as_ptr()) };
+    }
+}
+
+impl std::fmt::Debug for LibseatSession {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let alternate = f.alternate();
+        let mut debug = f.debug_struct("LibseatSession");
+
+        if alternate {
+            debug.field("raw", &self.raw);
+        }
+
+        debug
+            .field("seat", &self.seat_name())
+            .field("active", &self.is_active())
+            .field("devices", &self.devices.len())
+            .finish()
+    }
+}
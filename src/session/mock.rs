@@ -0,0 +1,132 @@
+use std::{
+    cell::RefCell,
+    io,
+    os::fd::{AsRawFd, OwnedFd, RawFd},
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use rustix::{fs::Mode, io::Errno};
+
+use crate::{
+    OFlags,
+    session::{SessionEvent, SessionOpener},
+};
+
+/// A session backend for tests, opening devices directly and pausing and resuming on demand.
+///
+/// The mock is a cheap handle, clones share the same state, so a test can keep one to drive
+/// the session while another is owned by the [`Session`](super::Session).
+///
+/// # Example usage
+///
+/// ```
+/// use colpetto::session::{MockSession, Session, SessionEvent};
+///
+/// let mock = MockSession::new();
+/// let session = Session::new(mock.clone());
+/// let libinput = session.libinput(None)?;
+///
+/// mock.pause();
+/// assert_eq!(session.dispatch(&libinput)?, [SessionEvent::Paused]);
+/// assert!(mock.open_devices().is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct MockSession {
+    state: Rc<RefCell<State>>,
+}
+
+#[derive(Debug)]
+struct State {
+    active: bool,
+    pending: Vec<SessionEvent>,
+    devices: Vec<(RawFd, PathBuf)>,
+    acknowledged_pauses: usize,
+}
+
+impl MockSession {
+    /// Creates an active session
+    pub fn new() -> Self {
+        Self {
+            state: Rc::new(RefCell::new(State {
+                active: true,
+                pending: Vec::new(),
+                devices: Vec::new(),
+                acknowledged_pauses: 0,
+            })),
+        }
+    }
+
+    /// Queues a pause, delivered on the next dispatch
+    pub fn pause(&self) {
+        self.state.borrow_mut().pending.push(SessionEvent::Paused);
+    }
+
+    /// Queues a resume, delivered on the next dispatch
+    pub fn resume(&self) {
+        self.state.borrow_mut().pending.push(SessionEvent::Resumed);
+    }
+
+    /// Returns the paths of the devices currently open through the session
+    pub fn open_devices(&self) -> Vec<PathBuf> {
+        self.state
+            .borrow()
+            .devices
+            .iter()
+            .map(|(_, path)| path.clone())
+            .collect()
+    }
+
+    /// Returns how many pauses were acknowledged, see [`SessionOpener::acknowledge_pause`]
+    pub fn acknowledged_pauses(&self) -> usize {
+        self.state.borrow().acknowledged_pauses
+    }
+}
+
+impl Default for MockSession {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SessionOpener for MockSession {
+    fn open_device(&mut self, path: &Path, flags: OFlags) -> io::Result<OwnedFd> {
+        let mut state = self.state.borrow_mut();
+
+        // Session managers refuse to open devices for inactive sessions
+        if !state.active {
+            return Err(Errno::PERM.into());
+        }
+
+        let fd = rustix::fs::open(path, flags | OFlags::CLOEXEC, Mode::empty())?;
+        state.devices.push((fd.as_raw_fd(), path.to_owned()));
+
+        Ok(fd)
+    }
+
+    fn close_device(&mut self, fd: OwnedFd) {
+        self.state
+            .borrow_mut()
+            .devices
+            .retain(|(open, _)| *open != fd.as_raw_fd());
+    }
+
+    fn dispatch(&mut self) -> io::Result<Vec<SessionEvent>> {
+        Ok(std::mem::take(&mut self.state.borrow_mut().pending))
+    }
+
+    fn deliver(&mut self, event: SessionEvent) {
+        // The state follows each event as the session handles it, so opens on resume succeed
+        // even if a later event pauses the session again
+        self.state.borrow_mut().active = event == SessionEvent::Resumed;
+    }
+
+    fn acknowledge_pause(&mut self) -> io::Result<()> {
+        self.state.borrow_mut().acknowledged_pauses += 1;
+        Ok(())
+    }
+
+    fn is_active(&self) -> bool {
+        self.state.borrow().active
+    }
+}
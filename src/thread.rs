@@ -0,0 +1,396 @@
+//! Running a libinput context on a dedicated thread.
+//!
+//! [`Libinput`] is neither `Send` nor `Sync`, so it cannot be moved into a multi-threaded runtime.
+//! [`spawn`] starts a thread owning the context and returns an [`InputThread`] handle that can be used from anywhere:
+//! it is a stream of [`OwnedEvent`]s and gives access to a [`Commands`] channel to configure devices,
+//! suspend and resume the context.
+//!
+//! # Example usage
+//!
+//! ```
+//! use colpetto::thread::{self, Config};
+//! use tokio_stream::StreamExt;
+//!
+//! let mut input = thread::spawn(Config::with_interface(Interface))?;
+//! let commands = input.commands();
+//!
+//! tokio::spawn(async move {
+//!     let _ = tokio::signal::ctrl_c().await;
+//!     commands.shutdown();
+//! });
+//!
+//! while let Some(event) = input.try_next().await? {
+//!     println!("Got \"{}\" event from \"{}\"", event.event_type(), event.device().name);
+//! }
+//!
+//! // Propagates the error or the panic that stopped the thread, if any.
+//! // Joining blocks until the thread stops, so it is moved off the async runtime.
+//! tokio::task::spawn_blocking(move || input.join()).await??;
+//! ```
+
+use std::{
+    ffi::{CStr, CString},
+    future::poll_fn,
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+    thread::JoinHandle,
+};
+
+use futures_core::Stream;
+use tokio::{
+    runtime,
+    sync::{mpsc, oneshot},
+};
+
+use crate::{
+    ConfigError, DeviceConfig, Error, Event, Libinput, Logger, OpenInterface, OwnedEvent, Result,
+};
+
+/// Configuration of the input thread, see [`spawn`]
+pub struct Config {
+    factory: Box<dyn FnOnce() -> Result<Libinput> + Send>,
+    seat: CString,
+    name: String,
+}
+
+impl Config {
+    /// Creates a configuration using `factory` to create the context on the input thread.
+    /// The context must not be assigned to a seat yet, this is done by the thread.
+    pub fn new<F>(factory: F) -> Self
+    where
+        F: FnOnce() -> Result<Libinput> + Send + 'static,
+    {
+        Self {
+            factory: Box::new(factory),
+            seat: c"seat0".to_owned(),
+            name: "colpetto".to_owned(),
+        }
+    }
+
+    /// Creates a configuration for a udev context opening devices through `interface`, logging with tracing if enabled
+    pub fn with_interface<I>(interface: I) -> Self
+    where
+        I: OpenInterface + Send + 'static,
+    {
+        Self::new(move || {
+            #[cfg(feature = "tracing")]
+            let logger = Some(Logger::tracing());
+            #[cfg(not(feature = "tracing"))]
+            let logger = None::<Logger>;
+
+            Libinput::with_interface(interface, logger)
+        })
+    }
+
+    /// Sets the seat the context is assigned to, `seat0` by default
+    pub fn seat(mut self, seat: &CStr) -> Self {
+        self.seat = seat.to_owned();
+        self
+    }
+
+    /// Sets the name of the thread, `colpetto` by default
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+}
+
+impl std::fmt::Debug for Config {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Config")
+            .field("seat", &self.seat)
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Error returned by [`Commands`]
+#[derive(Debug, thiserror::Error)]
+pub enum CommandError {
+    /// No device with the given sysname is present
+    #[error("No device named {0}")]
+    UnknownDevice(String),
+    /// The device refused the configuration
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    /// The context failed to carry out the command
+    #[error(transparent)]
+    Libinput(#[from] Error),
+    /// The input thread is not running anymore
+    #[error("The input thread stopped")]
+    Stopped,
+}
+
+enum Command {
+    ApplyConfig {
+        sysname: String,
+        config: DeviceConfig,
+        reply: oneshot::Sender<Result<(), CommandError>>,
+    },
+    Suspend(oneshot::Sender<Result<(), CommandError>>),
+    Resume(oneshot::Sender<Result<(), CommandError>>),
+    Shutdown,
+}
+
+/// Starts a thread running a libinput context, see the [module documentation](self).
+///
+/// The context is created and assigned to its seat on the new thread, failures to do so are returned by [`InputThread::join`].
+pub fn spawn(config: Config) -> io::Result<InputThread> {
+    let (events_tx, events) = mpsc::unbounded_channel();
+    let (commands, commands_rx) = mpsc::unbounded_channel();
+
+    let runtime = runtime::Builder::new_current_thread().enable_io().build()?;
+
+    let thread = std::thread::Builder::new()
+        .name(config.name.clone())
+        .spawn(move || runtime.block_on(run(config, events_tx, commands_rx)))?;
+
+    Ok(InputThread {
+        events,
+        commands: Commands { sender: commands },
+        thread: Some(thread),
+    })
+}
+
+async fn run(
+    config: Config,
+    events: mpsc::UnboundedSender<Result<OwnedEvent>>,
+    mut commands: mpsc::UnboundedReceiver<Command>,
+) -> Result<()> {
+    let mut libinput = (config.factory)()?;
+    libinput.udev_assign_seat(&config.seat)?;
+
+    let mut stream = libinput.event_stream()?;
+
+    loop {
+        let next = poll_fn(|cx| {
+            if let Poll::Ready(command) = commands.poll_recv(cx) {
+                return Poll::Ready(Next::Command(command));
+            }
+
+            Pin::new(&mut stream).poll_next(cx).map(Next::Event)
+        })
+        .await;
+
+        match next {
+            Next::Command(Some(command)) => {
+                if !handle(&libinput, command) {
+                    break;
+                }
+            }
+            Next::Command(None) | Next::Event(None) => break,
+            Next::Event(Some(event)) => {
+                // Nobody listening is not a reason to stop, commands may still come in
+                let _ = events.send(event.map(|event| event.to_owned()));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+enum Next {
+    Command(Option<Command>),
+    Event(Option<Result<Event>>),
+}
+
+/// Carries out a command, returning `false` if the thread should stop
+fn handle(libinput: &Libinput, command: Command) -> bool {
+    // Replies are dropped if the caller stopped waiting
+    match command {
+        Command::ApplyConfig {
+            sysname,
+            config,
+            reply,
+        } => {
+            let device = libinput
+                .handler()
+                .devices
+                .devices()
+                .into_iter()
+                .find(|device| device.sysname().to_bytes() == sysname.as_bytes());
+
+            let result = match device {
+                Some(device) => device.apply_config(&config).map_err(CommandError::from),
+                None => Err(CommandError::UnknownDevice(sysname)),
+            };
+
+            let _ = reply.send(result);
+        }
+        Command::Suspend(reply) => {
+            libinput.suspend();
+            let _ = reply.send(Ok(()));
+        }
+        Command::Resume(reply) => {
+            let _ = reply.send(libinput.resume().map_err(CommandError::from));
+        }
+        Command::Shutdown => return false,
+    }
+
+    true
+}
+
+/// A handle to the thread started by [`spawn`].
+///
+/// The handle is a stream of the events read by the thread, which ends when the thread stops.
+/// Dropping the handle shuts the thread down and waits for it, use [`join`](Self::join) to get its result.
+///
+/// Both dropping and joining block the current thread until the input thread stops. This is usually quick,
+/// the thread only finishes the event or command at hand, but it can take longer while the context is
+/// created or assigned to its seat. In async code, drop or join the handle with `spawn_blocking`.
+#[derive(Debug)]
+pub struct InputThread {
+    events: mpsc::UnboundedReceiver<Result<OwnedEvent>>,
+    commands: Commands,
+    thread: Option<JoinHandle<Result<()>>>,
+}
+
+impl InputThread {
+    /// Returns a channel to send commands to the thread
+    pub fn commands(&self) -> Commands {
+        self.commands.clone()
+    }
+
+    /// Shuts the thread down and waits for it to stop. Blocks the current thread, see [`InputThread`].
+    ///
+    /// # Errors
+    /// Returns the error that stopped the thread, e.g. if the context could not be created.
+    ///
+    /// # Panics
+    /// Resumes unwinding if the thread panicked
+    pub fn join(mut self) -> Result<()> {
+        self.commands.shutdown();
+
+        match self.thread.take().map(JoinHandle::join) {
+            Some(Ok(result)) => result,
+            Some(Err(payload)) => std::panic::resume_unwind(payload),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Stream for InputThread {
+    type Item = Result<OwnedEvent>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.events.poll_recv(cx)
+    }
+}
+
+impl Drop for InputThread {
+    fn drop(&mut self) {
+        self.commands.shutdown();
+
+        // Panics are only propagated by `join`, panicking in drop could abort
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Sends commands to the thread started by [`spawn`], cheap to clone
+#[derive(Debug, Clone)]
+pub struct Commands {
+    sender: mpsc::UnboundedSender<Command>,
+}
+
+impl Commands {
+    /// Applies `config` to the device with the given sysname, e.g. `event5`
+    pub async fn apply_config(
+        &self,
+        sysname: impl Into<String>,
+        config: DeviceConfig,
+    ) -> Result<(), CommandError> {
+        let sysname = sysname.into();
+
+        self.request(|reply| Command::ApplyConfig {
+            sysname,
+            config,
+            reply,
+        })
+        .await
+    }
+
+    /// Suspends the context, see [`Libinput::suspend`]
+    pub async fn suspend(&self) -> Result<(), CommandError> {
+        self.request(Command::Suspend).await
+    }
+
+    /// Resumes the context, see [`Libinput::resume`]
+    pub async fn resume(&self) -> Result<(), CommandError> {
+        self.request(Command::Resume).await
+    }
+
+    /// Asks the thread to stop, without waiting for it
+    pub fn shutdown(&self) {
+        let _ = self.sender.send(Command::Shutdown);
+    }
+
+    async fn request(
+        &self,
+        command: impl FnOnce(oneshot::Sender<Result<(), CommandError>>) -> Command,
+    ) -> Result<(), CommandError> {
+        let (reply, response) = oneshot::channel();
+
+        self.sender
+            .send(command(reply))
+            .map_err(|_| CommandError::Stopped)?;
+
+        response.await.map_err(|_| CommandError::Stopped)?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::{self, AssertUnwindSafe};
+
+    use super::*;
+
+    #[test]
+    fn join_returns_the_factory_error() {
+        let input = spawn(Config::new(|| Err(Error::Context))).unwrap();
+
+        assert!(matches!(input.join(), Err(Error::Context)));
+    }
+
+    #[test]
+    fn join_propagates_panics() {
+        let input =
+            spawn(Config::new(|| panic!("factory exploded")).name("colpetto-test")).unwrap();
+
+        let payload = panic::catch_unwind(AssertUnwindSafe(|| input.join())).unwrap_err();
+        assert_eq!(payload.downcast_ref::<&str>(), Some(&"factory exploded"));
+    }
+
+    #[test]
+    fn drop_swallows_panics() {
+        let input = spawn(Config::new(|| panic!("factory exploded"))).unwrap();
+
+        drop(input);
+    }
+
+    #[tokio::test]
+    async fn stopped_thread_ends_the_stream() {
+        let mut input = spawn(Config::new(|| Err(Error::Context))).unwrap();
+        let commands = input.commands();
+
+        assert!(
+            poll_fn(|cx| Pin::new(&mut input).poll_next(cx))
+                .await
+                .is_none()
+        );
+        assert!(matches!(
+            commands.suspend().await,
+            Err(CommandError::Stopped)
+        ));
+        assert!(matches!(
+            commands.apply_config("event0", DeviceConfig::new()).await,
+            Err(CommandError::Stopped)
+        ));
+
+        // Shutting down a stopped thread is a no-op
+        commands.shutdown();
+        assert!(matches!(input.join(), Err(Error::Context)));
+    }
+}
@@ -0,0 +1,142 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
+    ptr,
+    sync::Arc,
+    task::Waker,
+};
+
+use crate::{Device, DeviceGroup, DeviceInfo, sys};
+
+/// Keeps track of the live devices of a context, fed by the device events read through
+/// [`Libinput::get_event`](crate::Libinput::get_event).
+///
+/// Each tracked group stores a pointer back to its tracker in the libinput user data,
+/// which is how [`DeviceGroup::devices`] finds its members.
+#[derive(Default)]
+pub(crate) struct DeviceTracker {
+    devices: RefCell<HashSet<Device>>,
+    groups: RefCell<HashMap<DeviceGroup, Vec<Device>>>,
+    /// Wakers of the pending [`Removed`](crate::Removed) futures, keyed by the id of the future
+    removal_wakers: RefCell<HashMap<Device, Vec<(u64, Waker)>>>,
+    next_removal_id: Cell<u64>,
+    /// Snapshots shared by the owned events of the live devices, see [`Event::to_owned`](crate::Event::to_owned)
+    infos: RefCell<HashMap<Device, Arc<DeviceInfo>>>,
+}
+
+impl DeviceTracker {
+    pub(crate) fn device_added(&self, device: &Device) {
+        let group = device.device_group();
+
+        unsafe {
+            sys::libinput_device_group_set_user_data(group.as_raw(), ptr::from_ref(self) as *mut _);
+        }
+
+        self.devices.borrow_mut().insert(device.clone());
+        self.groups
+            .borrow_mut()
+            .entry(group)
+            .or_default()
+            .push(device.clone());
+    }
+
+    pub(crate) fn device_removed(&self, device: &Device) {
+        self.devices.borrow_mut().remove(device);
+        self.infos.borrow_mut().remove(device);
+
+        let group = device.device_group();
+        let mut groups = self.groups.borrow_mut();
+
+        if let Some(devices) = groups.get_mut(&group) {
+            devices.retain(|d| d != device);
+
+            if devices.is_empty() {
+                unsafe {
+                    sys::libinput_device_group_set_user_data(group.as_raw(), ptr::null_mut())
+                };
+                groups.remove(&group);
+            }
+        }
+
+        drop(groups);
+
+        let wakers = self.removal_wakers.borrow_mut().remove(device);
+        wakers
+            .into_iter()
+            .flatten()
+            .for_each(|(_, waker)| waker.wake());
+    }
+
+    pub(crate) fn is_live(&self, device: &Device) -> bool {
+        self.devices.borrow().contains(device)
+    }
+
+    pub(crate) fn devices(&self) -> Vec<Device> {
+        self.devices.borrow().iter().cloned().collect()
+    }
+
+    pub(crate) fn group_members(&self, group: &DeviceGroup) -> Vec<Device> {
+        self.groups.borrow().get(group).cloned().unwrap_or_default()
+    }
+
+    /// Returns the snapshot of `device`, built on first use. Devices not tracked get a fresh snapshot every time.
+    pub(crate) fn device_info(&self, device: &Device) -> Arc<DeviceInfo> {
+        if let Some(info) = self.infos.borrow().get(device) {
+            return info.clone();
+        }
+
+        let info = Arc::new(DeviceInfo::new(device));
+
+        if self.is_live(device) {
+            self.infos.borrow_mut().insert(device.clone(), info.clone());
+        }
+
+        info
+    }
+
+    pub(crate) fn next_removal_id(&self) -> u64 {
+        let id = self.next_removal_id.get();
+        self.next_removal_id.set(id + 1);
+        id
+    }
+
+    pub(crate) fn register_removal(&self, device: &Device, id: u64, waker: &Waker) {
+        let mut wakers = self.removal_wakers.borrow_mut();
+        let wakers = wakers.entry(device.clone()).or_default();
+
+        match wakers.iter_mut().find(|(other, _)| *other == id) {
+            Some((_, registered)) if registered.will_wake(waker) => {}
+            Some((_, registered)) => registered.clone_from(waker),
+            None => wakers.push((id, waker.clone())),
+        }
+    }
+
+    /// Forgets the waker of a dropped [`Removed`](crate::Removed) future, and the device once no future waits on it
+    pub(crate) fn unregister_removal(&self, device: &Device, id: u64) {
+        let mut removal_wakers = self.removal_wakers.borrow_mut();
+
+        if let Some(wakers) = removal_wakers.get_mut(device) {
+            wakers.retain(|(other, _)| *other != id);
+
+            if wakers.is_empty() {
+                removal_wakers.remove(device);
+            }
+        }
+    }
+
+    /// Forgets every tracked device. Must be called before the context is destroyed.
+    pub(crate) fn clear(&self) {
+        self.devices.borrow_mut().clear();
+        self.infos.borrow_mut().clear();
+
+        for (group, _) in self.groups.borrow_mut().drain() {
+            unsafe { sys::libinput_device_group_set_user_data(group.as_raw(), ptr::null_mut()) };
+        }
+
+        let wakers = std::mem::take(&mut *self.removal_wakers.borrow_mut());
+        wakers
+            .into_values()
+            .flatten()
+            .for_each(|(_, waker)| waker.wake());
+    }
+}
@@ -0,0 +1,91 @@
+use std::{ffi::CStr, marker::PhantomData, ptr};
+
+use devil::sys;
+
+/// An owned reference to a udev device, used to query properties without going through [`devil::Device`]
+pub(crate) struct UdevDevice {
+    raw: *mut sys::udev_device,
+}
+
+impl UdevDevice {
+    /// Takes ownership of a udev device reference
+    ///
+    /// # Safety
+    ///
+    /// The caller must pass a valid pointer or null, the reference is released on drop
+    pub(crate) unsafe fn from_raw(raw: *mut sys::udev_device) -> Option<Self> {
+        (!raw.is_null()).then_some(Self { raw })
+    }
+
+    pub(crate) fn property(&self, key: &CStr) -> Option<&CStr> {
+        unsafe { property(self.raw, key) }
+    }
+
+    /// Returns the closest parent in the given subsystem, if any
+    pub(crate) fn parent(&self, subsystem: &CStr) -> Option<UdevParent<'_>> {
+        let raw = unsafe {
+            sys::udev_device_get_parent_with_subsystem_devtype(
+                self.raw,
+                subsystem.as_ptr(),
+                ptr::null(),
+            )
+        };
+
+        (!raw.is_null()).then_some(UdevParent {
+            raw,
+            _device: PhantomData,
+        })
+    }
+
+    /// Iterates over every property of the device
+    pub(crate) fn properties(&self) -> impl Iterator<Item = (&CStr, &CStr)> {
+        let mut entry = unsafe { sys::udev_device_get_properties_list_entry(self.raw) };
+
+        std::iter::from_fn(move || {
+            while !entry.is_null() {
+                let current = entry;
+                entry = unsafe { sys::udev_list_entry_get_next(current) };
+
+                let name = unsafe { cstr(sys::udev_list_entry_get_name(current)) };
+                let value = unsafe { cstr(sys::udev_list_entry_get_value(current)) };
+
+                if let (Some(name), Some(value)) = (name, value) {
+                    return Some((name, value));
+                }
+            }
+
+            None
+        })
+    }
+}
+
+impl Drop for UdevDevice {
+    fn drop(&mut self) {
+        unsafe { sys::udev_device_unref(self.raw) };
+    }
+}
+
+/// A parent of a [`UdevDevice`], owned by its child
+pub(crate) struct UdevParent<'a> {
+    raw: *mut sys::udev_device,
+    _device: PhantomData<&'a UdevDevice>,
+}
+
+impl UdevParent<'_> {
+    pub(crate) fn property(&self, key: &CStr) -> Option<&CStr> {
+        unsafe { property(self.raw, key) }
+    }
+
+    /// Reads a sysfs attribute of the device, e.g. `name`
+    pub(crate) fn sysattr(&self, key: &CStr) -> Option<&CStr> {
+        unsafe { cstr(sys::udev_device_get_sysattr_value(self.raw, key.as_ptr())) }
+    }
+}
+
+unsafe fn property<'a>(device: *mut sys::udev_device, key: &CStr) -> Option<&'a CStr> {
+    unsafe { cstr(sys::udev_device_get_property_value(device, key.as_ptr())) }
+}
+
+unsafe fn cstr<'a>(ptr: *const std::ffi::c_char) -> Option<&'a CStr> {
+    (!ptr.is_null()).then(|| unsafe { CStr::from_ptr(ptr) })
+}